@@ -0,0 +1,66 @@
+/// Defines the alignment helpers for the given unsigned integer type, so the same
+/// arithmetic can be checked with the types narrower than `usize`, e.g. with `u16`,
+/// as on the 16-bit targets.
+macro_rules! align_helpers {
+    ($word:ty, $padding_needed:ident, $checked_align_up:ident) => {
+        #[inline]
+        pub(crate) const fn $padding_needed(addr: $word, align: $word) -> $word {
+            addr.wrapping_neg() & (align - 1)
+        }
+
+        #[inline]
+        pub(crate) const fn $checked_align_up(addr: $word, align: $word) -> Option<$word> {
+            match addr.checked_add(align - 1) {
+                Some(end) => Some(end & !(align - 1)),
+                None => None,
+            }
+        }
+    };
+}
+
+align_helpers!(usize, padding_needed_usize, checked_align_up_usize);
+#[cfg(test)]
+align_helpers!(u16, padding_needed_u16, checked_align_up_u16);
+#[cfg(test)]
+align_helpers!(u8, padding_needed_u8, checked_align_up_u8);
+
+/// Rounds the given `addr` up to the nearest multiple of `align`.
+///
+/// The `align` must be a power of two.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(static_box::align_up(13, 8), 16);
+/// assert_eq!(static_box::align_up(16, 8), 16);
+/// ```
+///
+/// # Panics
+///
+/// - If the result overflows `usize`.
+#[inline]
+pub const fn align_up(addr: usize, align: usize) -> usize {
+    match checked_align_up_usize(addr, align) {
+        Some(aligned) => aligned,
+        None => panic!("The aligned address overflows `usize`"),
+    }
+}
+
+/// Returns the number of bytes that have to be skipped from the given `addr` to make it
+/// aligned to `align`.
+///
+/// This is the same padding that [`Box::new`](crate::Box::new) applies to the beginning
+/// of the provided buffer.
+///
+/// The `align` must be a power of two.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(static_box::padding_needed(13, 8), 3);
+/// assert_eq!(static_box::padding_needed(16, 8), 0);
+/// ```
+#[inline]
+pub const fn padding_needed(addr: usize, align: usize) -> usize {
+    padding_needed_usize(addr, align)
+}
@@ -0,0 +1,77 @@
+use core::ops::{Deref, DerefMut};
+
+/// A wrapper that aligns the `buf` to the alignment of the `A` marker type.
+///
+/// The marker types [`A2`], [`A4`], [`A8`], [`A16`], [`A32`] and [`A64`] correspond
+/// to the alignment of the same number of bytes.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::{Aligned, Box, A8};
+///
+/// let mut mem = Aligned::<A8, _>::new([0_u8; 32]);
+/// let value = Box::<dyn Display>::new_aligned_to_buffer(&mut mem, 42_u64);
+/// assert_eq!(value.alignment_waste(), 0);
+/// assert_eq!(value.to_string(), "42");
+/// ```
+#[repr(C)]
+pub struct Aligned<A, B: ?Sized> {
+    align: [A; 0],
+    buf: B,
+}
+
+impl<A, B> Aligned<A, B> {
+    /// Wraps the given buffer.
+    #[inline]
+    pub const fn new(buf: B) -> Self {
+        Self { align: [], buf }
+    }
+
+    /// Returns the wrapped buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+}
+
+impl<A, B: ?Sized> Deref for Aligned<A, B> {
+    type Target = B;
+
+    #[inline]
+    fn deref(&self) -> &B {
+        &self.buf
+    }
+}
+
+impl<A, B: ?Sized> DerefMut for Aligned<A, B> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut B {
+        &mut self.buf
+    }
+}
+
+/// The marker type of the 2 bytes alignment.
+#[repr(align(2))]
+pub struct A2;
+
+/// The marker type of the 4 bytes alignment.
+#[repr(align(4))]
+pub struct A4;
+
+/// The marker type of the 8 bytes alignment.
+#[repr(align(8))]
+pub struct A8;
+
+/// The marker type of the 16 bytes alignment.
+#[repr(align(16))]
+pub struct A16;
+
+/// The marker type of the 32 bytes alignment.
+#[repr(align(32))]
+pub struct A32;
+
+/// The marker type of the 64 bytes alignment.
+#[repr(align(64))]
+pub struct A64;
@@ -0,0 +1,249 @@
+use core::{
+    alloc::Layout,
+    marker::{PhantomData, Unsize},
+    ops::{Index, IndexMut},
+    ptr::{self, DynMetadata},
+};
+
+use crate::{padding_needed, DynTrait};
+
+/// A slice of trait objects of the same concrete type stored in the provided memory.
+///
+/// Since all elements have the same concrete type, the dynamic metadata is stored only once,
+/// and the elements are placed contiguously after it, just like in a regular array.
+///
+/// # Examples
+///
+/// ```
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 64];
+/// let closures = Box::<dyn Fn() -> usize>::new_boxed_slice(
+///     &mut mem,
+///     (0..4).map(|i| move || i * 10),
+/// );
+/// assert_eq!(closures.len(), 4);
+/// assert_eq!(closures[2](), 20);
+/// ```
+pub struct BoxedSlice<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    align_offset: usize,
+    len: usize,
+    mem: &'m mut [u8],
+    phantom: PhantomData<T>,
+}
+
+impl<'m, T> BoxedSlice<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    /// Places all the `values` into the specified `mem` buffer. The user should provide enough
+    /// memory to store all of the values with their metadata considering alignment requirements.
+    ///
+    /// If the iterator yields fewer elements than it reported, the slice contains only
+    /// the yielded elements, the excess elements are not taken.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the values.
+    pub fn new<Value, I>(mem: &'m mut [u8], values: I) -> Self
+    where
+        Value: Unsize<T>,
+        I: IntoIterator<Item = Value>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let values = values.into_iter();
+        let len = values.len();
+
+        // Get dynamic metadata for the value type, the pointer itself is never dereferenced.
+        let meta = ptr::metadata(ptr::null::<Value>() as *const T);
+        // Compute memory layout to store the values + their metadata, the array of a huge
+        // number of values may not fit into the address space.
+        let (layout, offset) = Layout::array::<Value>(len)
+            .and_then(|values_layout| Layout::for_value(&meta).extend(values_layout))
+            .unwrap_or_else(|_| Self::insufficient::<Value>(mem.len(), usize::MAX));
+
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        let align_offset = padding_needed(raw_ptr as usize, layout.align());
+
+        let total_len = align_offset
+            .checked_add(layout.size())
+            .unwrap_or(usize::MAX);
+        let buf_len = mem.len();
+        // Check that the provided buffer has sufficient capacity to store the given values.
+        if total_len > buf_len {
+            Self::insufficient::<Value>(buf_len, total_len);
+        }
+
+        let values_ptr = unsafe {
+            let ptr = raw_ptr.add(align_offset);
+            // Store dynamic metadata at the beginning of the given memory buffer.
+            ptr.cast::<DynMetadata<T>>().write(meta);
+            ptr.add(offset).cast::<Value>()
+        };
+
+        // The slice length grows with each stored value, so if the iterator panics,
+        // only the already stored values will be dropped.
+        let mut slice = Self {
+            align_offset,
+            len: 0,
+            mem,
+            phantom: PhantomData,
+        };
+        for (index, value) in values.take(len).enumerate() {
+            unsafe { values_ptr.add(index).write(value) };
+            slice.len += 1;
+        }
+        slice
+    }
+
+    /// Returns the number of elements in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the slice has a length of 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to an element, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            Some(unsafe { &*self.value_ptr(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to an element, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            Some(unsafe { &mut *self.value_mut_ptr(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the slice.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len).map(move |index| unsafe { &*self.value_ptr(index) })
+    }
+
+    /// Returns an iterator that allows modifying each element.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        let (offset, stride, meta) = self.offset_stride_meta();
+        let values_ptr = unsafe { self.mem.as_mut_ptr().add(self.align_offset).add(offset) };
+        // Each index is visited only once, so the returned references never alias.
+        (0..self.len).map(move |index| unsafe {
+            &mut *ptr::from_raw_parts_mut(values_ptr.add(index * stride).cast(), meta)
+        })
+    }
+
+    #[cold]
+    fn insufficient<Value>(available: usize, needed: usize) -> ! {
+        panic!(
+            "Not enough memory to store the specified values of type `{}` (got: {}, needed: {})",
+            core::any::type_name::<Value>(),
+            available,
+            needed,
+        );
+    }
+
+    #[inline]
+    fn meta(&self) -> DynMetadata<T> {
+        unsafe { *self.mem.as_ptr().add(self.align_offset).cast() }
+    }
+
+    #[inline]
+    fn offset_stride_meta(&self) -> (usize, usize, DynMetadata<T>) {
+        let meta = self.meta();
+        let value_layout = meta.layout();
+        let (_, offset) = Layout::for_value(&meta).extend(value_layout).unwrap();
+        (offset, value_layout.pad_to_align().size(), meta)
+    }
+
+    #[inline]
+    fn value_ptr(&self, index: usize) -> *const T {
+        let (offset, stride, meta) = self.offset_stride_meta();
+        unsafe {
+            let ptr = self
+                .mem
+                .as_ptr()
+                .add(self.align_offset)
+                .add(offset)
+                .add(index * stride)
+                .cast::<()>();
+            ptr::from_raw_parts(ptr, meta)
+        }
+    }
+
+    #[inline]
+    fn value_mut_ptr(&mut self, index: usize) -> *mut T {
+        let (offset, stride, meta) = self.offset_stride_meta();
+        unsafe {
+            let ptr = self
+                .mem
+                .as_mut_ptr()
+                .add(self.align_offset)
+                .add(offset)
+                .add(index * stride)
+                .cast::<()>();
+            ptr::from_raw_parts_mut(ptr, meta)
+        }
+    }
+}
+
+impl<'m, T> Index<usize> for BoxedSlice<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        let len = self.len;
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<'m, T> IndexMut<usize> for BoxedSlice<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<'m, T> Drop for BoxedSlice<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn drop(&mut self) {
+        for value in self.iter_mut() {
+            unsafe {
+                ptr::drop_in_place::<T>(value);
+            }
+        }
+    }
+}
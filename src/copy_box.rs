@@ -0,0 +1,146 @@
+use core::{
+    alloc::Layout,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    ptr::{self, DynMetadata},
+};
+
+use crate::{Box, DynTrait};
+
+/// A box storing a value of a `Copy` type, which doesn't call the destructor of the value
+/// through the metadata when it is dropped.
+///
+/// Only values of `Copy` types can be stored, since they have no destructors:
+///
+/// ```compile_fail,E0277
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 64];
+/// let value = Box::<dyn Display>::new_copy(&mut mem, String::from("42"));
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// for i in 0..3 {
+///     let value = Box::<dyn Display>::new_copy(&mut mem, i);
+///     assert_eq!(value.to_string(), i.to_string());
+/// }
+/// ```
+pub struct CopyBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    inner: ManuallyDrop<Box<'m, T>>,
+}
+
+impl<'m, T> CopyBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    // The box must store a value of a `Copy` type.
+    #[inline]
+    pub(crate) fn new(inner: Box<'m, T>) -> Self {
+        Self {
+            inner: ManuallyDrop::new(inner),
+        }
+    }
+
+    /// Copies the stored value with its metadata into the specified `mem` buffer.
+    ///
+    /// The value is copied bytewise, no user code is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::fmt::Debug;
+    /// use static_box::Box;
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let mut other_mem = [0_u8; 32];
+    ///
+    /// let value = Box::<dyn Debug>::new_copy(&mut mem, (1, 'x'));
+    /// let copy = value.copy_to(&mut other_mem);
+    /// assert_eq!(format!("{:?}", copy.as_ref()), "(1, 'x')");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn copy_to<'n>(&self, mem: &'n mut [u8]) -> CopyBox<'n, T> {
+        let meta = self.inner.metadata();
+        let (layout, offset) = Layout::for_value(&meta).extend(meta.layout()).unwrap();
+
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        let align_offset = Box::<T>::record_align_offset::<T>(raw_ptr as usize, mem.len(), layout)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        // The stored value is `Copy`, so its bytes can be duplicated.
+        unsafe {
+            let ptr = raw_ptr.add(align_offset);
+            ptr.cast::<DynMetadata<T>>().write(meta);
+            ptr::copy_nonoverlapping(
+                self.inner.value_ptr().cast::<u8>(),
+                ptr.add(offset),
+                meta.size_of(),
+            );
+        }
+
+        let mut copy = Box::from_raw_parts(mem, align_offset);
+        copy.type_id = self.inner.type_id;
+        CopyBox::new(copy)
+    }
+
+    /// Converts the box into a regular [`Box`].
+    #[inline]
+    pub fn into_box(self) -> Box<'m, T> {
+        ManuallyDrop::into_inner(self.inner)
+    }
+}
+
+impl<'m, T> AsRef<T> for CopyBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self.inner.as_ref()
+    }
+}
+
+impl<'m, T> AsMut<T> for CopyBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        self.inner.as_mut()
+    }
+}
+
+impl<'m, T> Deref for CopyBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<'m, T> DerefMut for CopyBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.as_mut()
+    }
+}
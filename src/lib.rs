@@ -2,6 +2,10 @@
 #![feature(ptr_metadata)]
 #![feature(unsize)]
 #![feature(const_pin)]
+#![feature(const_fn)]
+#![feature(const_panic)]
+#![feature(fn_traits)]
+#![feature(unboxed_closures)]
 // #![deny(missing_docs)]
 
 //! # Overview
@@ -58,7 +62,7 @@
 //! // Calculate the amount of memory needed to store this object.
 //! let total_len = {
 //!     let layout = Box::<dyn Display>::layout_of_dyn(&value);
-//!     let align_offset = mem.as_ptr().align_offset(layout.align());
+//!     let align_offset = static_box::padding_needed(mem.as_ptr() as usize, layout.align());
 //!     layout.size() + align_offset
 //! };
 //! let (head, _tail) = mem.split_at_mut(total_len);
@@ -72,12 +76,21 @@
 //! At the moment this crate can only store dynamic objects, but it's hard to imagine
 //! use cases where there is a need to store sized objects in this box.
 //!
+//! # Crate features
+//!
+//! - `alloc` enables the conversions from and to the heap allocated
+//!   [`alloc::boxed::Box`](https://doc.rust-lang.org/alloc/boxed/struct.Box.html).
+//!
 //! # Minimum Supported `rustc` Version
 //!
 //! This crate uses the following unstable features:
 //!
 //! - [`ptr_metadata`](https://doc.rust-lang.org/unstable-book/library-features/ptr-metadata.html)
 //! - [`unsize`](https://doc.rust-lang.org/unstable-book/library-features/unsize.html)
+//! - [`const_fn`](https://doc.rust-lang.org/unstable-book/language-features/const-fn.html)
+//! - [`const_panic`](https://doc.rust-lang.org/unstable-book/language-features/const-panic.html)
+//! - [`fn_traits`](https://doc.rust-lang.org/unstable-book/library-features/fn-traits.html)
+//! - [`unboxed_closures`](https://doc.rust-lang.org/unstable-book/language-features/unboxed-closures.html)
 //!
 //! In other words, the crate's supported **nightly** `rustc` version is `1.53.0`, but there
 //! is no guarantee that this code will work fine on the newest versions.
@@ -101,106 +114,1430 @@
 //! expensive in some cases.
 //!
 
-use core::{
-    alloc::Layout,
-    marker::{PhantomData, Unsize},
-    ops::{Deref, DerefMut},
-    ptr::{self, DynMetadata, NonNull, Pointee},
-};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::{
+    alloc::Layout,
+    any::{Any, TypeId},
+    hash::Hasher,
+    marker::{PhantomData, Unsize},
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut, Range},
+    ptr::{self, DynMetadata, NonNull, Pointee},
+    slice,
+};
+
+pub use align::{align_up, padding_needed};
+pub use aligned::{Aligned, A16, A2, A32, A4, A64, A8};
+pub use boxed_slice::BoxedSlice;
+pub use copy_box::CopyBox;
+pub use manual_box::ManualBox;
+pub use slab::{Slab, SlabBox};
+pub use spare_writer::SpareWriter;
+pub use static_box_cell::StaticBox;
+pub use static_mem::StaticMem;
+pub use typed_box::TypedBox;
+pub use typed_slot::TypedSlot;
+
+mod align;
+mod aligned;
+mod boxed_slice;
+mod copy_box;
+mod manual_box;
+mod slab;
+mod spare_writer;
+mod static_box_cell;
+mod static_mem;
+#[cfg(test)]
+mod tests;
+mod typed_box;
+mod typed_slot;
+
+#[inline]
+fn meta_offset_layout<T, Value>(value: &Value) -> Option<(DynMetadata<T>, Layout, usize)>
+where
+    T: ?Sized + DynTrait,
+    Value: Unsize<T> + ?Sized,
+{
+    // Get dynamic metadata for the given value.
+    let meta = ptr::metadata(value as &T);
+    // Compute memory layout to store the value + its metadata.
+    let meta_layout = Layout::for_value(&meta);
+    let value_layout = Layout::for_value(value);
+    let (layout, offset) = meta_layout.extend(value_layout).ok()?;
+    Some((meta, layout, offset))
+}
+
+/// Places a `value` into the specified `mem` buffer, inferring the trait object type
+/// from the context.
+///
+/// This is the same as [`Box::new`], but it doesn't require to specify the trait object
+/// type explicitly when it is already known from the type annotation or the function
+/// signature.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// // Instead of the turbofish syntax...
+/// let value = Box::<dyn Display>::new(&mut mem, 42);
+/// assert_eq!(value.to_string(), "42");
+/// drop(value);
+///
+/// // ...the trait object type can be inferred from the binding type.
+/// let value: Box<dyn Display> = static_box::emplace(&mut mem, 42);
+/// assert_eq!(value.to_string(), "42");
+/// ```
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// fn make_display(mem: &mut [u8]) -> Box<dyn Display> {
+///     static_box::emplace(mem, "Hello world!")
+/// }
+///
+/// let mut mem = [0_u8; 32];
+/// assert_eq!(make_display(&mut mem).to_string(), "Hello world!");
+/// ```
+///
+/// # Panics
+///
+/// - If the provided buffer is insufficient to store the value.
+#[inline]
+pub fn emplace<T, Value>(mem: &mut [u8], value: Value) -> Box<'_, T>
+where
+    T: ?Sized + DynTrait,
+    Value: Unsize<T>,
+{
+    Box::new(mem, value)
+}
+
+/// The error type returned when the provided buffer is insufficient to store a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsufficientMemory {
+    /// The length of the provided buffer.
+    pub available: usize,
+    /// The number of bytes needed to store the value with its metadata, including
+    /// the alignment padding.
+    pub needed: usize,
+    /// The name of the value type, as returned by [`core::any::type_name`].
+    pub type_name: &'static str,
+    /// Whether the buffer is too small for the record itself, or only for the record
+    /// with the alignment padding.
+    pub reason: Reason,
+}
+
+impl InsufficientMemory {
+    #[inline]
+    pub(crate) fn new<V: ?Sized>(available: usize, needed: usize, record_size: usize) -> Self {
+        let reason = if record_size > available {
+            Reason::Size
+        } else {
+            Reason::Alignment
+        };
+        Self {
+            available,
+            needed,
+            type_name: core::any::type_name::<V>(),
+            reason,
+        }
+    }
+}
+
+/// The reason why the provided buffer is insufficient to store a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The buffer is shorter than the record with the value and its metadata.
+    Size,
+    /// The record itself fits into the buffer, but not after the alignment padding
+    /// is skipped at the beginning of the buffer.
+    Alignment,
+}
+
+impl core::fmt::Display for InsufficientMemory {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Not enough memory to store the specified value of type `{}` (got: {}, needed: {})",
+            self.type_name, self.available, self.needed,
+        )
+    }
+}
+
+/// The error type returned when the provided buffer lies outside of the allowed memory region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRegion;
+
+impl core::fmt::Display for OutOfRegion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("The provided buffer lies outside of the allowed memory region")
+    }
+}
+
+struct AlignmentCheck<T, Value, A>(PhantomData<(*const T, Value, A)>)
+where
+    T: ?Sized + DynTrait,
+    Value: Unsize<T>;
+
+impl<T, Value, A> AlignmentCheck<T, Value, A>
+where
+    T: ?Sized + DynTrait,
+    Value: Unsize<T>,
+{
+    // The constant is evaluated for each used combination of the types, so an insufficient
+    // alignment is reported as a compilation error.
+    const OK: () = assert!(
+        mem::align_of::<A>() >= Box::<T>::record_align::<Value>(),
+        "The buffer alignment is less than the alignment of the record"
+    );
+}
+
+/// A trait object type, i.e. `dyn Trait`, whose pointer metadata is a [`DynMetadata`].
+///
+/// This trait is implemented for all such types and cannot be implemented for other
+/// types. It is used as the bound of the [`Box`] type parameter, so using a sized type
+/// is reported as an unsatisfied `DynTrait` bound.
+pub trait DynTrait: Pointee<Metadata = DynMetadata<Self>> {}
+
+impl<T> DynTrait for T where T: ?Sized + Pointee<Metadata = DynMetadata<T>> {}
+
+/// A box that uses the provided memory to store dynamic objects.
+///
+/// The `T` type parameter must be a trait object type, i.e. `dyn Trait`. Sized types are
+/// rejected at compile time with the "the following trait bounds were not satisfied:
+/// `u32: DynTrait`" error:
+///
+/// ```compile_fail,E0599
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// let value = Box::<u32>::new(&mut mem, 42_u32);
+/// ```
+///
+/// The stored trait object may borrow non-`'static` data, in this case the trait object type
+/// should mention the lifetime of the borrowed data, e.g. `dyn Fn() + 'a`, and the box cannot
+/// outlive it:
+///
+/// ```compile_fail,E0597
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// let len = {
+///     let value = String::from("borrowed");
+///     Box::<dyn Fn() -> usize + '_>::new(&mut mem, || value.len())
+/// };
+/// assert_eq!(len(), 8);
+/// ```
+pub struct Box<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    align_offset: usize,
+    mem: &'m mut [u8],
+    // The type of the stored value, if it was recorded by the `new_typed` constructor.
+    type_id: Option<TypeId>,
+    // The hook registered by the `on_drop` method.
+    drop_hook: Option<fn()>,
+    phantom: PhantomData<T>,
+}
+
+impl<'m, T> Box<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    /// Places a `value` into the specified `mem` buffer. The user should provide enough memory
+    /// to store the value with its metadata considering alignment requirements.
+    ///
+    /// The record is placed at the first correctly aligned address of the buffer. Any other
+    /// aligned window starts later and thus has less room, so if the value doesn't fit
+    /// at this place, it doesn't fit anywhere in the buffer.
+    ///
+    /// The `Value` must be sized, since it is moved into the buffer by value. An already
+    /// unsized value cannot be moved out of its place, and copying its bytes would duplicate
+    /// the ownership of the resources it holds, so it cannot be used as a source:
+    ///
+    /// ```compile_fail,E0277
+    /// use core::fmt::Display;
+    /// use static_box::Box;
+    ///
+    /// let source: &dyn Display = &42;
+    /// let mut mem = [0_u8; 32];
+    /// let value = Box::<dyn Display>::new(&mut mem, *source);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn new<Value>(mem: &'m mut [u8], value: Value) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        match Self::try_new(mem, value) {
+            Ok(new_box) => new_box,
+            Err((_, err)) => panic!("{}", err),
+        }
+    }
+
+    /// Attempts to place a `value` into the specified `mem` buffer.
+    ///
+    /// Unlike [`new`](Self::new), this method never panics, all failure modes, such as
+    /// an insufficient buffer, an unsatisfiable alignment or an arithmetic overflow, are
+    /// reported as an error. In this case, the value is returned back to the caller and
+    /// the buffer is left untouched.
+    pub fn try_new<Value>(
+        mem: &'m mut [u8],
+        value: Value,
+    ) -> Result<Self, (Value, InsufficientMemory)>
+    where
+        Value: Unsize<T>,
+    {
+        let (meta, layout, offset) = match meta_offset_layout(&value) {
+            Some(meta_offset_layout) => meta_offset_layout,
+            None => {
+                let err = InsufficientMemory::new::<Value>(mem.len(), usize::MAX, usize::MAX);
+                return Err((value, err));
+            }
+        };
+        Self::write_record(mem, value, meta, layout, offset)
+    }
+
+    /// Places a `value` into the specified `mem` buffer using the previously computed
+    /// `layout` of the record instead of computing it again.
+    ///
+    /// The `layout` must be the one returned by [`layout_of`](Self::layout_of) or
+    /// [`layout_of_dyn`](Self::layout_of_dyn) for the `Value` type. The check is cheap,
+    /// since the layout of the `Value` type is known at compile time.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    /// - If the `layout` doesn't match the `Value` type.
+    pub fn new_with_layout<Value>(mem: &'m mut [u8], value: Value, layout: Layout) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        assert_eq!(
+            layout,
+            Self::layout_of::<Value>(),
+            "The provided layout doesn't match the value type"
+        );
+
+        let meta = ptr::metadata(&value as &T);
+        // The value is placed at the end of the record, there is no trailing padding.
+        let offset = layout.size() - mem::size_of::<Value>();
+        match Self::write_record(mem, value, meta, layout, offset) {
+            Ok(new_box) => new_box,
+            Err((_, err)) => panic!("{}", err),
+        }
+    }
+
+    /// Takes the value out of the [`ManuallyDrop`] wrapper and places it into the specified
+    /// `mem` buffer, so the box becomes responsible for dropping the value.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    #[inline]
+    pub fn from_manually_drop<Value>(mem: &'m mut [u8], value: ManuallyDrop<Value>) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        Self::new(mem, ManuallyDrop::into_inner(value))
+    }
+
+    /// Moves the value out of the heap allocated `value` box into the specified `mem` buffer
+    /// and frees the heap allocation.
+    ///
+    /// The value is copied directly from the heap into the buffer, so large values don't
+    /// pass through the stack.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    #[cfg(feature = "alloc")]
+    pub fn from_alloc_box<Value>(mem: &'m mut [u8], value: alloc::boxed::Box<Value>) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        let layout = Self::layout_of::<Value>();
+        // The value is placed at the end of the record, there is no trailing padding.
+        let offset = layout.size() - mem::size_of::<Value>();
+
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        let align_offset = Self::record_align_offset::<Value>(raw_ptr as usize, mem.len(), layout)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        let value = alloc::boxed::Box::into_raw(value);
+        unsafe {
+            let ptr = raw_ptr.add(align_offset);
+            ptr.cast::<DynMetadata<T>>()
+                .write(ptr::metadata(value as *const T));
+            ptr::copy_nonoverlapping(value.cast::<u8>(), ptr.add(offset), mem::size_of::<Value>());
+            // The value has been moved out, so only the heap allocation is freed.
+            drop(alloc::boxed::Box::from_raw(
+                value.cast::<ManuallyDrop<Value>>(),
+            ));
+        }
+
+        Self::from_raw_parts(mem, align_offset)
+    }
+
+    /// Places a `value` into the specified `mem` buffer and records the [`TypeId`] of
+    /// its concrete type, which can be obtained by the
+    /// [`stored_type_id`](Self::stored_type_id) method even if `T` is not a `dyn Any`.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    #[inline]
+    pub fn new_typed<Value>(mem: &'m mut [u8], value: Value) -> Self
+    where
+        Value: Unsize<T> + 'static,
+    {
+        let mut this = Self::new(mem, value);
+        this.type_id = Some(TypeId::of::<Value>());
+        this
+    }
+
+    /// Places a default value of the `Value` type into the specified `mem` buffer and returns
+    /// a [`TypedBox`], so the initialization of the value can be completed in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::fmt::Debug;
+    /// use static_box::Box;
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct Frame {
+    ///     header: u16,
+    ///     payload: [u8; 4],
+    /// }
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let mut frame = Box::<dyn Debug>::new_partial::<Frame>(&mut mem);
+    /// frame.value_mut().header = 0xCAFE;
+    /// frame.value_mut().payload = *b"ping";
+    ///
+    /// let frame = frame.into_box();
+    /// assert_eq!(
+    ///     format!("{:?}", frame.as_ref()),
+    ///     "Frame { header: 51966, payload: [112, 105, 110, 103] }"
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    #[inline]
+    pub fn new_partial<Value>(mem: &'m mut [u8]) -> TypedBox<'m, T, Value>
+    where
+        Value: Unsize<T> + Default,
+    {
+        Self::new_with_handle(mem, Value::default())
+    }
+
+    /// Places a `value` of a `Copy` type into the specified `mem` buffer and returns
+    /// a [`CopyBox`], which doesn't call the destructor of the value when it is dropped.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    #[inline]
+    pub fn new_copy<Value>(mem: &'m mut [u8], value: Value) -> CopyBox<'m, T>
+    where
+        Value: Unsize<T> + Copy,
+    {
+        CopyBox::new(Self::new(mem, value))
+    }
+
+    /// Places a `value` into the specified `mem` buffer and returns a [`TypedBox`], which
+    /// gives access to the stored value by its concrete type as well.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    #[inline]
+    pub fn new_with_handle<Value>(mem: &'m mut [u8], value: Value) -> TypedBox<'m, T, Value>
+    where
+        Value: Unsize<T>,
+    {
+        TypedBox::new(Self::new(mem, value))
+    }
+
+    /// Places a `value` into the buffer taken from the specified [`StaticMem`].
+    ///
+    /// # Panics
+    ///
+    /// - If the buffer has already been taken.
+    /// - If the buffer is insufficient to store the value.
+    pub fn new_in_static<Value, const N: usize>(mem: &'m StaticMem<N>, value: Value) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        let mem = mem
+            .take()
+            .expect("The static buffer has already been taken");
+        Self::new(mem, value)
+    }
+
+    /// Places a `value` into the specified `mem` buffer and returns the created box together
+    /// with the layout of the stored record, i.e. of the metadata followed by the value.
+    ///
+    /// The returned layout is the same as [`layout_of_dyn`](Self::layout_of_dyn) gives for
+    /// the value, it doesn't include the alignment padding at the beginning of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn new_reporting<Value>(mem: &'m mut [u8], value: Value) -> (Self, Layout)
+    where
+        Value: Unsize<T>,
+    {
+        let layout = Self::layout_of::<Value>();
+        (Self::new(mem, value), layout)
+    }
+
+    /// Places a `value` at the very beginning of the specified aligned buffer.
+    ///
+    /// Unlike [`new`](Self::new), this method doesn't compute the alignment padding, since
+    /// the buffer is already aligned by the `A` marker type, so the record always starts
+    /// at offset zero. The alignment is checked at compile time, see
+    /// [`assert_buffer_alignment`](Self::assert_buffer_alignment).
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn new_aligned_to_buffer<Value, A, const N: usize>(
+        mem: &'m mut Aligned<A, [u8; N]>,
+        value: Value,
+    ) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        Self::assert_buffer_alignment::<Value, A>();
+
+        let layout = Self::layout_of::<Value>();
+        // Both sides are constants, so the check is optimized out.
+        if layout.size() > N {
+            let err = InsufficientMemory::new::<Value>(N, layout.size(), layout.size());
+            panic!("{}", err);
+        }
+
+        let meta = ptr::metadata(&value as &T);
+        // The value is placed at the end of the record, there is no trailing padding.
+        let offset = layout.size() - mem::size_of::<Value>();
+        unsafe {
+            let ptr = mem.as_mut_ptr();
+            ptr.cast::<DynMetadata<T>>().write(meta);
+            ptr.add(offset).cast::<Value>().write(value);
+        }
+
+        Self::from_raw_parts(&mut mem[..], 0)
+    }
+
+    /// Creates a value by the fallible constructor `f` and places it into the specified
+    /// `mem` buffer.
+    ///
+    /// If `f` returns an error, the buffer is left untouched and the error is returned.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn try_from_fn<Value, E, F>(mem: &'m mut [u8], f: F) -> Result<Self, E>
+    where
+        Value: Unsize<T>,
+        F: FnOnce() -> Result<Value, E>,
+    {
+        let value = f()?;
+        Ok(Self::new(mem, value))
+    }
+
+    /// Places a `value` into the specified uninitialized `mem` buffer, e.g. into memory
+    /// of a `.noinit` linker section, which keeps arbitrary bytes after a reset.
+    ///
+    /// The previous content of the buffer is never read, see
+    /// [`try_new_uninit`](Self::try_new_uninit) for details.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn new_uninit<Value>(mem: &'m mut [MaybeUninit<u8>], value: Value) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        match Self::try_new_uninit(mem, value) {
+            Ok(new_box) => new_box,
+            Err((_, err)) => panic!("{}", err),
+        }
+    }
+
+    /// Attempts to place a `value` into the specified uninitialized `mem` buffer, so
+    /// the whole buffer doesn't have to be zeroed beforehand.
+    ///
+    /// Only the [`used`](Self::used) part of the buffer is zeroed and kept by the created
+    /// box, since the remaining bytes are not initialized. If the buffer is insufficient, the value
+    /// is returned back with an error, and the buffer is left untouched.
+    pub fn try_new_uninit<Value>(
+        mem: &'m mut [MaybeUninit<u8>],
+        value: Value,
+    ) -> Result<Self, (Value, InsufficientMemory)>
+    where
+        Value: Unsize<T>,
+    {
+        let layout = Self::layout_of::<Value>();
+        let used =
+            match Self::record_align_offset::<Value>(mem.as_ptr() as usize, mem.len(), layout) {
+                Ok(align_offset) => align_offset + layout.size(),
+                Err(err) => return Err((value, err)),
+            };
+
+        // A `&mut [u8]` must not point to uninitialized bytes, so only the used prefix
+        // is zeroed to initialize the padding bytes of the record.
+        let mem = unsafe {
+            let ptr = mem.as_mut_ptr().cast::<u8>();
+            ptr::write_bytes(ptr, 0, used);
+            slice::from_raw_parts_mut(ptr, used)
+        };
+        Self::try_new(mem, value)
+    }
+
+    /// Creates a box from the `len` raw bytes of a value at the `bytes` pointer and its
+    /// dynamic metadata, e.g. previously obtained by the [`value_bytes`](Self::value_bytes)
+    /// and [`metadata`](Self::metadata) methods.
+    ///
+    /// The bytes are copied into the specified `mem` buffer with the same record layout
+    /// as [`new`](Self::new) uses.
+    ///
+    /// # Safety
+    ///
+    /// - The `meta` must be the metadata of a concrete type that implements `T`, and
+    ///   the vtable it points to must be valid for the whole lifetime of the box, so it must
+    ///   come from the same build of the program.
+    /// - The `bytes` must be valid for reads of `len` bytes, and they must not overlap with
+    ///   the `mem` buffer.
+    /// - The bytes must be a valid value of that concrete type, which the box takes
+    ///   ownership of. Thus, bytes of a value that owns resources must not be used twice,
+    ///   otherwise these resources are dropped twice.
+    ///
+    /// # Panics
+    ///
+    /// - If the `len` doesn't match the size of the concrete type.
+    /// - If the provided buffer is insufficient to store the value.
+    /// - If the bytes overlap with the `mem` buffer, in debug builds only.
+    pub unsafe fn from_value_bytes(
+        mem: &'m mut [u8],
+        bytes: *const MaybeUninit<u8>,
+        len: usize,
+        meta: DynMetadata<T>,
+    ) -> Self {
+        assert_eq!(
+            len,
+            meta.size_of(),
+            "The length of the bytes doesn't match the size of the value type"
+        );
+        // Only the addresses are compared, the bytes are not accessed before the check.
+        debug_assert!(
+            {
+                let buf = mem.as_ptr_range();
+                let (src_start, src_end) = (bytes as usize, (bytes as usize).wrapping_add(len));
+                src_end <= buf.start as usize || buf.end as usize <= src_start
+            },
+            "The bytes overlap with the provided buffer"
+        );
+
+        let (layout, offset) = Layout::for_value(&meta).extend(meta.layout()).unwrap();
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        let align_offset = Self::record_align_offset::<T>(raw_ptr as usize, mem.len(), layout)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        let ptr = raw_ptr.add(align_offset);
+        ptr.cast::<DynMetadata<T>>().write(meta);
+        ptr::copy_nonoverlapping(bytes.cast::<u8>(), ptr.add(offset), len);
+
+        Self::from_raw_parts(mem, align_offset)
+    }
+
+    /// Returns the offset within the specified `mem` buffer at which the bytes of a value
+    /// of the `Value` type are placed by [`new`](Self::new), or `None` if the buffer is
+    /// insufficient to store the value.
+    ///
+    /// The bytes of the value may be written at this offset beforehand, e.g. by a DMA
+    /// transfer, and then adopted by [`new_in_place_from_bytes`](Self::new_in_place_from_bytes).
+    pub fn value_offset_in<Value>(mem: &[u8]) -> Option<usize>
+    where
+        Value: Unsize<T>,
+    {
+        let layout = Self::layout_of::<Value>();
+        let align_offset =
+            Self::record_align_offset::<Value>(mem.as_ptr() as usize, mem.len(), layout).ok()?;
+        Some(align_offset + layout.size() - mem::size_of::<Value>())
+    }
+
+    /// Creates a box owning a value of the `Value` type, whose bytes have already been
+    /// written into the `mem` buffer at the [`value_offset_in`](Self::value_offset_in) offset.
+    ///
+    /// Only the dynamic metadata is written in front of the value, the value itself
+    /// is not copied. Unlike [`from_value_bytes`](Self::from_value_bytes), there is no
+    /// separate source of the bytes, so there is nothing for the buffer to overlap with.
+    ///
+    /// # Safety
+    ///
+    /// The bytes at the offset must be a valid value of the `Value` type, the box takes
+    /// ownership of it and drops it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::fmt::Debug;
+    /// use static_box::Box;
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let offset = Box::<dyn Debug>::value_offset_in::<[u8; 4]>(&mem).unwrap();
+    /// // Somebody writes the bytes of the value into the buffer.
+    /// mem[offset..offset + 4].copy_from_slice(b"ping");
+    ///
+    /// let value = unsafe { Box::<dyn Debug>::new_in_place_from_bytes::<[u8; 4]>(&mut mem) };
+    /// assert_eq!(format!("{:?}", value.as_ref()), format!("{:?}", b"ping"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub unsafe fn new_in_place_from_bytes<Value>(mem: &'m mut [u8]) -> Self
+    where
+        Value: Unsize<T>,
+    {
+        let layout = Self::layout_of::<Value>();
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        let align_offset = Self::record_align_offset::<Value>(raw_ptr as usize, mem.len(), layout)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        // Get dynamic metadata for the value type, the pointer itself is never dereferenced.
+        let meta = ptr::metadata(ptr::null::<Value>() as *const T);
+        raw_ptr
+            .add(align_offset)
+            .cast::<DynMetadata<T>>()
+            .write(meta);
+
+        Self::from_raw_parts(mem, align_offset)
+    }
+
+    /// Places a `value` into the specified `mem` buffer only if the whole buffer lies within
+    /// the given memory `region`, e.g. within a specific SRAM bank.
+    ///
+    /// Otherwise, the value is returned back with an [`OutOfRegion`] error.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn new_within<Value>(
+        region: Range<*const u8>,
+        mem: &'m mut [u8],
+        value: Value,
+    ) -> Result<Self, (Value, OutOfRegion)>
+    where
+        Value: Unsize<T>,
+    {
+        let buf = mem.as_ptr_range();
+        if buf.start < region.start || buf.end > region.end {
+            return Err((value, OutOfRegion));
+        }
+        Ok(Self::new(mem, value))
+    }
+
+    /// Places a `value` into the specified `mem` buffer, runs `f` with the created box
+    /// and drops the box before returning the result of `f`.
+    ///
+    /// Thus, the box never outlives this call, and the buffer can be reused right after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::fmt::Display;
+    /// use static_box::Box;
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let first = Box::<dyn Display>::scoped(&mut mem, 42, |value| value.to_string());
+    /// let second = Box::<dyn Display>::scoped(&mut mem, 'x', |value| value.to_string());
+    /// assert_eq!(first, "42");
+    /// assert_eq!(second, "x");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the value.
+    pub fn scoped<Value, R, F>(mem: &mut [u8], value: Value, f: F) -> R
+    where
+        Value: Unsize<T>,
+        F: FnOnce(&mut Box<'_, T>) -> R,
+    {
+        let mut scoped_box = Box::new(mem, value);
+        f(&mut scoped_box)
+    }
+
+    /// Places all the `values` into the specified `mem` buffer as a [`BoxedSlice`].
+    ///
+    /// All the values have the same concrete type, so their metadata is stored only once.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store the values.
+    #[inline]
+    pub fn new_boxed_slice<Value, I>(mem: &'m mut [u8], values: I) -> BoxedSlice<'m, T>
+    where
+        Value: Unsize<T>,
+        I: IntoIterator<Item = Value>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        BoxedSlice::new(mem, values)
+    }
+
+    /// Calculates layout describing a record that could be used
+    /// to allocate backing structure for `Value`.
+    #[inline]
+    pub fn layout_of_dyn<Value>(value: &Value) -> Layout
+    where
+        Value: Unsize<T> + ?Sized,
+    {
+        meta_offset_layout::<T, Value>(value).unwrap().1
+    }
+
+    /// Calculates layout describing a record that could be used
+    /// to allocate backing structure for `Value`.
+    ///
+    /// Unlike [`layout_of_dyn`](Self::layout_of_dyn), this function doesn't require
+    /// an instance of the `Value`, since the layout of a sized type and of its metadata
+    /// are determined by the type itself.
+    #[inline]
+    pub fn layout_of<Value>() -> Layout
+    where
+        Value: Unsize<T>,
+    {
+        Layout::new::<DynMetadata<T>>()
+            .extend(Layout::new::<Value>())
+            .unwrap()
+            .0
+    }
+
+    /// Returns the buffer size that is always sufficient to store a value of the `Value` type
+    /// with its metadata, regardless of the buffer alignment.
+    ///
+    /// Unlike [`layout_of_dyn`](Self::layout_of_dyn), this function can be used in constant
+    /// expressions, so the result can be used to declare a buffer:
+    ///
+    /// ```
+    /// use core::fmt::Display;
+    /// use static_box::Box;
+    ///
+    /// const LEN: usize = Box::<dyn Display>::capacity_for::<u64>();
+    ///
+    /// let mut mem = [0_u8; LEN];
+    /// let value = Box::<dyn Display>::new(&mut mem, 42_u64);
+    /// assert_eq!(value.to_string(), "42");
+    /// ```
+    ///
+    /// The result includes the worst case alignment padding, which is `align - 1` bytes.
+    ///
+    /// # Panics
+    ///
+    /// - If the capacity overflows `usize`, which is possible on the 16-bit targets.
+    pub const fn capacity_for<Value>() -> usize
+    where
+        Value: Unsize<T>,
+    {
+        let meta_size = mem::size_of::<DynMetadata<T>>();
+        let value_size = mem::size_of::<Value>();
+        let value_align = mem::align_of::<Value>();
+
+        // The same offset as `Layout::extend` gives for the value placed after the metadata.
+        let value_offset = align_up(meta_size, value_align);
+        let capacity = match value_offset.checked_add(value_size) {
+            Some(record_size) => record_size.checked_add(Self::record_align::<Value>() - 1),
+            None => None,
+        };
+        match capacity {
+            Some(capacity) => capacity,
+            None => panic!("The capacity for the value overflows `usize`"),
+        }
+    }
+
+    /// Returns the alignment of the record storing a value of the `Value` type with
+    /// its metadata.
+    pub const fn record_align<Value>() -> usize
+    where
+        Value: Unsize<T>,
+    {
+        let meta_align = mem::align_of::<DynMetadata<T>>();
+        let value_align = mem::align_of::<Value>();
+        if meta_align > value_align {
+            meta_align
+        } else {
+            value_align
+        }
+    }
+
+    /// Checks at compile time that a buffer aligned by the `A` marker type, e.g.
+    /// [`Aligned<A4, _>`](Aligned), is sufficiently aligned to store a value of the `Value`
+    /// type without the alignment padding.
+    ///
+    /// ```
+    /// use core::fmt::Display;
+    /// use static_box::{Box, A8};
+    ///
+    /// Box::<dyn Display>::assert_buffer_alignment::<u64, A8>();
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use core::fmt::Display;
+    /// use static_box::{Box, A4};
+    ///
+    /// Box::<dyn Display>::assert_buffer_alignment::<u64, A4>();
+    /// ```
+    ///
+    /// Keep in mind that the record is at least as aligned as the metadata, which is
+    /// a pointer.
+    #[inline]
+    pub fn assert_buffer_alignment<Value, A>()
+    where
+        Value: Unsize<T>,
+    {
+        let _ = AlignmentCheck::<T, Value, A>::OK;
+    }
+
+    /// Swaps the values stored in this box and in the `other` one.
+    ///
+    /// Each box keeps its own buffer, the stored values with their metadata are
+    /// exchanged bytewise and then realigned according to the buffer they were moved to.
+    ///
+    /// # Panics
+    ///
+    /// - If one of the buffers is insufficient to store the value of the other box.
+    pub fn swap(&mut self, other: &mut Self) {
+        let (self_layout, ..) = self.layout_meta();
+        let (other_layout, ..) = other.layout_meta();
+
+        // Both records are exchanged as is, so both buffers should be able to hold
+        // the longest of them.
+        let swap_len = core::cmp::max(
+            self.align_offset + self_layout.size(),
+            other.align_offset + other_layout.size(),
+        );
+        // After that each record should be moved to the correctly aligned place.
+        let self_align_offset = padding_needed(self.mem.as_ptr() as usize, other_layout.align());
+        let other_align_offset = padding_needed(other.mem.as_ptr() as usize, self_layout.align());
 
-#[cfg(test)]
-mod tests;
+        let self_needed = core::cmp::max(
+            swap_len,
+            self_align_offset.saturating_add(other_layout.size()),
+        );
+        let other_needed = core::cmp::max(
+            swap_len,
+            other_align_offset.saturating_add(self_layout.size()),
+        );
+        assert!(
+            self_needed <= self.mem.len() && other_needed <= other.mem.len(),
+            "Not enough memory to swap the stored values (got: {} and {}, needed: {} and {})",
+            self.mem.len(),
+            other.mem.len(),
+            self_needed,
+            other_needed,
+        );
 
-#[inline]
-fn meta_offset_layout<T, Value>(value: &Value) -> (DynMetadata<T>, Layout, usize)
-where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
-    Value: Unsize<T> + ?Sized,
-{
-    // Get dynamic metadata for the given value.
-    let meta = ptr::metadata(value as &T);
-    // Compute memory layout to store the value + its metadata.
-    let meta_layout = Layout::for_value(&meta);
-    let value_layout = Layout::for_value(value);
-    let (layout, offset) = meta_layout.extend(value_layout).unwrap();
-    (meta, layout, offset)
-}
+        unsafe {
+            let self_ptr = self.mem.as_mut_ptr();
+            let other_ptr = other.mem.as_mut_ptr();
 
-/// A box that uses the provided memory to store dynamic objects.
-pub struct Box<'m, T>
-where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
-{
-    align_offset: usize,
-    mem: &'m mut [u8],
-    phantom: PhantomData<T>,
-}
+            ptr::swap_nonoverlapping(self_ptr, other_ptr, swap_len);
+            ptr::copy(
+                self_ptr.add(other.align_offset),
+                self_ptr.add(self_align_offset),
+                other_layout.size(),
+            );
+            ptr::copy(
+                other_ptr.add(self.align_offset),
+                other_ptr.add(other_align_offset),
+                self_layout.size(),
+            );
+        }
 
-impl<'m, T> Box<'m, T>
-where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
-{
-    /// Places a `value` into the specified `mem` buffer. The user should provide enough memory
-    /// to store the value with its metadata considering alignment requirements.
+        self.align_offset = self_align_offset;
+        other.align_offset = other_align_offset;
+        mem::swap(&mut self.type_id, &mut other.type_id);
+        mem::swap(&mut self.drop_hook, &mut other.drop_hook);
+    }
+
+    /// Moves the stored value out of the buffer into a heap allocated
+    /// [`alloc::boxed::Box`], e.g. if the value must outlive the buffer.
+    #[cfg(feature = "alloc")]
+    pub fn into_alloc_box(self) -> alloc::boxed::Box<T> {
+        let this = ManuallyDrop::new(self);
+        let meta = this.meta();
+        let layout = meta.layout();
+
+        let ptr = if layout.size() == 0 {
+            // Zero sized values don't need an allocation, only an aligned pointer.
+            layout.align() as *mut u8
+        } else {
+            let ptr = unsafe { alloc::alloc::alloc(layout) };
+            if ptr.is_null() {
+                alloc::alloc::handle_alloc_error(layout);
+            }
+            ptr
+        };
+
+        // The value is moved out, so the box must not drop it.
+        unsafe {
+            ptr::copy_nonoverlapping(this.value_ptr().cast::<u8>(), ptr, layout.size());
+            alloc::boxed::Box::from_raw(ptr::from_raw_parts_mut(ptr.cast(), meta))
+        }
+    }
+
+    /// Registers the `hook` that is called right after the destructor of the stored value,
+    /// e.g. to signal that a resource has been torn down. The previously registered hook
+    /// is replaced.
     ///
-    /// # Panics
+    /// The hook is called only if the box drops the value, it is not called if the value
+    /// is moved out of the box.
     ///
-    /// - If the provided buffer is insufficient to store the value.
-    pub fn new<Value>(mem: &'m mut [u8], value: Value) -> Self
+    /// # Examples
+    ///
+    /// ```
+    /// use core::{
+    ///     fmt::Display,
+    ///     sync::atomic::{AtomicBool, Ordering},
+    /// };
+    /// use static_box::Box;
+    ///
+    /// static RELEASED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let mut value = Box::<dyn Display>::new(&mut mem, 42);
+    /// value.on_drop(|| RELEASED.store(true, Ordering::SeqCst));
+    ///
+    /// drop(value);
+    /// assert!(RELEASED.load(Ordering::SeqCst));
+    /// ```
+    #[inline]
+    pub fn on_drop(&mut self, hook: fn()) -> &mut Self {
+        self.drop_hook = Some(hook);
+        self
+    }
+
+    /// Returns the number of bytes of the buffer used by the box, including the leading
+    /// alignment padding.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.align_offset + self.layout_meta().0.size()
+    }
+
+    /// Returns the address of the beginning of the buffer, including the alignment padding.
+    #[inline]
+    pub fn buffer_addr(&self) -> usize {
+        self.mem.as_ptr() as usize
+    }
+
+    /// Returns a non-null pointer to the stored value, e.g. to link the boxed values into
+    /// an intrusive structure.
+    ///
+    /// The pointer refers to the value inside the buffer, so it remains valid when the box
+    /// itself is moved, but it dangles once the box is dropped or the value is relocated by
+    /// the [`swap`](Self::swap) method. The pointer is derived from a shared reference,
+    /// so it must only be used to read the value.
+    #[inline]
+    pub fn as_non_null(&self) -> NonNull<T> {
+        // The pointer to the value inside the borrowed buffer is never null.
+        unsafe { NonNull::new_unchecked(self.value_ptr() as *mut T) }
+    }
+
+    /// Returns a [`fmt::Write`](core::fmt::Write) sink over the unused tail of the buffer,
+    /// i.e. over the bytes after the [`used`](Self::used) part, so it can be used as
+    /// a scratch space for formatted text.
+    ///
+    /// The writer starts from the beginning of the spare bytes each time the method
+    /// is called.
+    #[inline]
+    pub fn spare_writer(&mut self) -> SpareWriter<'_> {
+        let used = self.used();
+        SpareWriter::new(&mut self.mem[used..])
+    }
+
+    /// Returns the whole buffer of the box, including the stored record.
+    ///
+    /// # Safety
+    ///
+    /// - The caller must not change the bytes of the stored record, i.e. the
+    ///   [`used`](Self::used) part of the buffer except the leading alignment padding,
+    ///   otherwise the box may read corrupted metadata or drop a corrupted value.
+    /// - The caller must not read the bytes of the [`used`](Self::used) part of the buffer
+    ///   that may be uninitialized despite the `u8` type: the leading alignment padding,
+    ///   the gap between the metadata and the value, and the padding bytes of the value.
+    ///   Only the spare bytes past the stored record can be freely read.
+    #[inline]
+    pub unsafe fn buffer_mut(&mut self) -> &mut [u8] {
+        self.mem
+    }
+
+    /// Returns the number of bytes skipped at the beginning of the buffer to align
+    /// the stored record.
+    ///
+    /// A non-zero value means that the buffer could be shrunk by aligning it to the alignment
+    /// of the record, e.g. by declaring it with a `#[repr(align(N))]` wrapper.
+    #[inline]
+    pub fn alignment_waste(&self) -> usize {
+        self.align_offset
+    }
+
+    /// Splits off the unused tail of the buffer and returns it, the box keeps only
+    /// the [`used`](Self::used) part of the buffer.
+    ///
+    /// The returned slice has the same lifetime as the original buffer, so it can be used
+    /// while the box is still alive.
+    pub fn shrink_buffer(&mut self) -> &'m mut [u8] {
+        let used = self.used();
+        let (head, tail) = mem::take(&mut self.mem).split_at_mut(used);
+        self.mem = head;
+        tail
+    }
+
+    /// Runs `f` over the stored value and returns the box back, so the calls can be chained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use static_box::Box;
+    ///
+    /// trait Settings {
+    ///     fn set(&mut self, key: &'static str, value: u32);
+    ///     fn get(&self, key: &'static str) -> Option<u32>;
+    /// }
+    ///
+    /// # impl Settings for Vec<(&'static str, u32)> {
+    /// #     fn set(&mut self, key: &'static str, value: u32) {
+    /// #         self.push((key, value));
+    /// #     }
+    /// #
+    /// #     fn get(&self, key: &'static str) -> Option<u32> {
+    /// #         self.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    /// #     }
+    /// # }
+    /// #
+    /// let mut mem = [0_u8; 64];
+    /// let mut settings = Box::<dyn Settings>::new(&mut mem, Vec::new());
+    /// settings
+    ///     .update(|s| s.set("baud", 9600))
+    ///     .update(|s| s.set("enabled", 1));
+    /// assert_eq!(settings.get("baud"), Some(9600));
+    /// ```
+    #[inline]
+    pub fn update<F>(&mut self, f: F) -> &mut Self
     where
-        Value: Unsize<T>,
+        F: FnOnce(&mut T),
     {
-        let (meta, layout, offset) = meta_offset_layout(&value);
-        assert!(layout.size() > 0, "Unsupported value layot");
+        f(self.as_mut());
+        self
+    }
 
-        // Construct a box to move the specified memory into the necessary location.
-        let mut new_box = Self {
-            align_offset: 0,
-            mem,
-            phantom: PhantomData,
-        };
+    /// Converts the box into a [`ManualBox`], which drops the stored value only on
+    /// an explicit [`ManualBox::drop_value`] call.
+    #[inline]
+    pub fn into_manual(self) -> ManualBox<'m, T> {
+        ManualBox::new(self)
+    }
 
-        let raw_ptr = new_box.mem.as_mut().as_mut_ptr();
-        // Compute the offset that needs to be applied to the pointer in order to make
-        // it aligned correctly.
-        new_box.align_offset = raw_ptr.align_offset(layout.align());
-
-        let total_len = new_box.align_offset + layout.size();
-        let buf_len = new_box.mem.as_ref().len();
-        // Check that the provided buffer has sufficient capacity to store the given value.
-        if total_len > buf_len {
-            // At the moment we cannot rely on the regular drop implementation because
-            // the box is in an inconsistent state.
-            core::mem::forget(new_box);
-            panic!(
-                "Not enough memory to store the specified value (got: {}, needed: {})",
-                buf_len, total_len,
-            );
+    /// Consumes the box and returns a shared reference to the stored value with
+    /// the lifetime of the buffer.
+    ///
+    /// The value is never dropped, it stays in the buffer as long as the buffer is borrowed,
+    /// so the resources it owns are leaked, just like with [`core::mem::forget`].
+    pub fn freeze(self) -> &'m T {
+        let this = ManuallyDrop::new(self);
+        // The record is read through the box before its buffer reference is copied out,
+        // so the copy is the only reference used afterwards.
+        let (_, value_offset, meta) = this.layout_meta();
+        let start = this.align_offset + value_offset;
+        // The value is leaked, so the box must not drop it.
+        let mem: &'m mut [u8] = unsafe { ptr::read(&this.mem) };
+        let ptr = mem[start..].as_ptr().cast::<()>();
+        unsafe { &*ptr::from_raw_parts(ptr, meta) }
+    }
+
+    /// Returns the bytes of the stored metadata.
+    #[inline]
+    pub fn meta_bytes(&self) -> &[u8] {
+        let start = self.align_offset;
+        &self.mem[start..start + mem::size_of::<DynMetadata<T>>()]
+    }
+
+    /// Returns the bytes of the stored value.
+    ///
+    /// The bytes are returned as [`MaybeUninit`], since the padding bytes of the value
+    /// are uninitialized.
+    #[inline]
+    pub fn value_bytes(&self) -> &[MaybeUninit<u8>] {
+        let (_, value_offset, meta) = self.layout_meta();
+        let start = self.align_offset + value_offset;
+        let bytes = &self.mem[start..start + meta.size_of()];
+        // The `MaybeUninit<u8>` has the same layout as `u8`, and it may be uninitialized.
+        unsafe { slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len()) }
+    }
+
+    /// Runs `f` over the bytes of the stored record, i.e. of the metadata followed
+    /// by the value, for example to encrypt them while the box isn't in use.
+    ///
+    /// The bytes are passed as [`MaybeUninit`], since the gap between the metadata and
+    /// the value and the padding bytes of the value are uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// When `f` returns, the record bytes must be exactly the same as before the call,
+    /// i.e. any transformation must be reverted inside `f`. The box must not be used
+    /// while the bytes are transformed, and `f` must not panic in the middle of the
+    /// transformation, otherwise the box drops a value with corrupted metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::{fmt::Display, mem::MaybeUninit};
+    /// use static_box::Box;
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let mut value = Box::<dyn Display>::new(&mut mem, 42_i32);
+    /// unsafe {
+    ///     value.with_encrypted_buffer(|record| {
+    ///         // The record of an `i32` has neither gaps nor padding, so all its bytes
+    ///         // are initialized.
+    ///         let xor = |byte: &mut MaybeUninit<u8>| {
+    ///             *byte = MaybeUninit::new(byte.assume_init() ^ 0x5A);
+    ///         };
+    ///         record.iter_mut().for_each(xor);
+    ///         // The box is parked here, the record is restored before returning.
+    ///         record.iter_mut().for_each(xor);
+    ///     })
+    /// };
+    /// assert_eq!(value.to_string(), "42");
+    /// ```
+    pub unsafe fn with_encrypted_buffer<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut [MaybeUninit<u8>]),
+    {
+        let (layout, ..) = self.layout_meta();
+        let start = self.align_offset;
+        let record = &mut self.mem[start..start + layout.size()];
+        // The `MaybeUninit<u8>` has the same layout as `u8`, and it may be uninitialized.
+        f(slice::from_raw_parts_mut(
+            record.as_mut_ptr().cast(),
+            record.len(),
+        ));
+    }
+
+    /// Performs a best-effort check that the stored metadata looks plausible.
+    ///
+    /// The check verifies that the vtable pointer is non-null and aligned, and only then
+    /// reads the size and the alignment from the vtable to verify that the alignment is
+    /// a power of two, and that the whole record fits into the buffer. It cannot verify
+    /// that the pointer really points to a vtable of the stored value, so a successful check
+    /// doesn't guarantee that the box can be dereferenced. It catches zeroed metadata
+    /// and a misaligned pointer, or a readable vtable-like memory with an implausible
+    /// layout.
+    ///
+    /// # Safety
+    ///
+    /// If the stored vtable pointer is non-null and aligned, it must point to readable
+    /// memory of at least three pointers, e.g. to any vtable of the program.
+    pub unsafe fn validate(&self) -> bool {
+        let meta_size = mem::size_of::<DynMetadata<T>>();
+        let meta_align = mem::align_of::<DynMetadata<T>>();
+
+        let record_addr = (self.mem.as_ptr() as usize).wrapping_add(self.align_offset);
+        let meta_fits = self
+            .align_offset
+            .checked_add(meta_size)
+            .map_or(false, |end| end <= self.mem.len());
+        if !meta_fits || padding_needed(record_addr, meta_align) != 0 {
+            return false;
+        }
+
+        // The dynamic metadata is a single pointer to the vtable.
+        let vtable_addr = self
+            .mem
+            .as_ptr()
+            .add(self.align_offset)
+            .cast::<usize>()
+            .read();
+        if vtable_addr == 0 || padding_needed(vtable_addr, mem::align_of::<usize>()) != 0 {
+            return false;
         }
 
+        // The pointer is readable according to the safety contract.
+        let meta = self.meta();
+        let value_align = meta.align_of();
+        if !value_align.is_power_of_two() || padding_needed(record_addr, value_align) != 0 {
+            return false;
+        }
+
+        // The same offset as `Layout::extend` gives for the value placed after the metadata.
+        meta_size
+            .checked_add(value_align - 1)
+            .map(|end| end & !(value_align - 1))
+            .and_then(|value_offset| value_offset.checked_add(meta.size_of()))
+            .and_then(|record_size| record_size.checked_add(self.align_offset))
+            .map_or(false, |end| end <= self.mem.len())
+    }
+
+    /// Returns the dynamic metadata of the stored value.
+    #[inline]
+    pub fn metadata(&self) -> DynMetadata<T> {
+        self.meta()
+    }
+
+    /// Returns the [`TypeId`] of the stored value, or `None` if the box was not created
+    /// by the [`new_typed`](Self::new_typed) constructor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::{any::TypeId, fmt::Display};
+    /// use static_box::Box;
+    ///
+    /// let mut mem = [0_u8; 32];
+    /// let value = Box::<dyn Display>::new_typed(&mut mem, 42_u32);
+    /// assert_eq!(value.stored_type_id(), Some(TypeId::of::<u32>()));
+    /// ```
+    #[inline]
+    pub fn stored_type_id(&self) -> Option<TypeId> {
+        self.type_id
+    }
+
+    /// Returns `true` if both boxes store values of the same concrete type, i.e. their
+    /// metadata refer to the same vtable.
+    ///
+    /// The vtables are compared by address, so the result is only a hint. The compiler
+    /// may emit several copies of the vtable for the same type, e.g. in different codegen
+    /// units, so this method may return `false` for the values of the same type. And it may
+    /// merge identical vtables of different types, so this method may also return `true`
+    /// for the values of different types. Use [`stored_type_id`](Self::stored_type_id)
+    /// for an exact answer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::fmt::Display;
+    /// use static_box::Box;
+    ///
+    /// let mut mem = [0_u8; 96];
+    /// let (first_mem, rest) = mem.split_at_mut(32);
+    /// let (second_mem, third_mem) = rest.split_at_mut(32);
+    ///
+    /// let first = Box::<dyn Display>::new(first_mem, 1_u32);
+    /// let second = Box::<dyn Display>::new(second_mem, 2_u32);
+    /// let third = Box::<dyn Display>::new(third_mem, '3');
+    /// assert!(first.same_type(&second));
+    /// assert!(!first.same_type(&third));
+    /// ```
+    #[inline]
+    pub fn same_type(&self, other: &Self) -> bool {
+        self.meta() == other.meta()
+    }
+
+    /// Returns the offset of the stored value relative to the beginning of its record,
+    /// i.e. to the stored metadata.
+    #[inline]
+    pub fn value_offset(&self) -> usize {
+        self.layout_meta().1
+    }
+
+    /// Returns a reference to the stored value using the previously obtained
+    /// [`value_offset`](Self::value_offset) and [`metadata`](Self::metadata).
+    ///
+    /// Unlike [`as_ref`](AsRef::as_ref), this method doesn't read the metadata and doesn't
+    /// compute the record layout, so it can be used in hot loops.
+    ///
+    /// # Safety
+    ///
+    /// The `value_offset` and `meta` must be the values returned by the corresponding methods
+    /// of this box, and the box must not have been changed since then, for example
+    /// by [`swap`](Self::swap).
+    #[inline]
+    pub unsafe fn as_ref_with_meta(&self, value_offset: usize, meta: DynMetadata<T>) -> &T {
+        let ptr = self
+            .mem
+            .as_ptr()
+            .add(self.align_offset)
+            .add(value_offset)
+            .cast::<()>();
+        &*ptr::from_raw_parts(ptr, meta)
+    }
+
+    fn write_record<Value>(
+        mem: &'m mut [u8],
+        value: Value,
+        meta: DynMetadata<T>,
+        layout: Layout,
+        offset: usize,
+    ) -> Result<Self, (Value, InsufficientMemory)>
+    where
+        Value: Unsize<T>,
+    {
+        // Take the raw pointer to the buffer without creating intermediate references,
+        // it is the only pointer used to write the record.
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        // Compute the offset that needs to be applied to the pointer in order to make
+        // it aligned correctly, and check that the provided buffer has sufficient capacity
+        // to store the given value.
+        let align_offset =
+            match Self::record_align_offset::<Value>(raw_ptr as usize, mem.len(), layout) {
+                Ok(align_offset) => align_offset,
+                Err(err) => return Err((value, err)),
+            };
+
         unsafe {
-            let ptr = NonNull::new(raw_ptr.add(new_box.align_offset)).unwrap();
+            let ptr = raw_ptr.add(align_offset);
             // Store dynamic metadata at the beginning of the given memory buffer.
-            ptr.cast::<DynMetadata<T>>().as_ptr().write(meta);
+            ptr.cast::<DynMetadata<T>>().write(meta);
             // Store the value in the remainder of the memory buffer.
-            ptr.cast::<u8>()
-                .as_ptr()
-                .add(offset)
-                .cast::<Value>()
-                .write(value);
+            ptr.add(offset).cast::<Value>().write(value);
+        }
+
+        // The box is constructed only after the record has been written, so it is never
+        // observed in an inconsistent state.
+        Ok(Self::from_raw_parts(mem, align_offset))
+    }
 
-            new_box
+    // Returns the offset at which a record with the given `layout` is placed in the buffer
+    // of the `buf_len` bytes starting at the `buf_addr`, or the error for the `V` type if
+    // the buffer is insufficient.
+    #[inline]
+    fn record_align_offset<V: ?Sized>(
+        buf_addr: usize,
+        buf_len: usize,
+        layout: Layout,
+    ) -> Result<usize, InsufficientMemory> {
+        let align_offset = padding_needed(buf_addr, layout.align());
+        match align_offset.checked_add(layout.size()) {
+            Some(total_len) if total_len <= buf_len => Ok(align_offset),
+            needed => {
+                let needed = needed.unwrap_or(usize::MAX);
+                Err(InsufficientMemory::new::<V>(buf_len, needed, layout.size()))
+            }
         }
     }
 
-    /// Calculates layout describing a record that could be used
-    /// to allocate backing structure for `Value`.
+    // The buffer must contain a valid record at the `align_offset`.
     #[inline]
-    pub fn layout_of_dyn<Value>(value: &Value) -> Layout
-    where
-        Value: Unsize<T> + ?Sized,
-    {
-        meta_offset_layout::<T, Value>(value).1
+    fn from_raw_parts(mem: &'m mut [u8], align_offset: usize) -> Self {
+        Self {
+            align_offset,
+            mem,
+            type_id: None,
+            drop_hook: None,
+            phantom: PhantomData,
+        }
     }
 
     #[inline]
@@ -246,9 +1583,131 @@ where
     }
 }
 
+impl<'m> Box<'m, dyn Any + Send> {
+    /// Attempts to move the stored value out of the box and downcast it to a concrete type.
+    ///
+    /// Returns the box back if the stored value has a different type.
+    pub fn downcast_send<V: Any + Send>(self) -> Result<V, Self> {
+        if self.is::<V>() {
+            let this = ManuallyDrop::new(self);
+            // The value is moved out, so the box must not drop it.
+            unsafe { Ok(this.value_ptr().cast::<V>().read()) }
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<'m, Args, F> FnOnce<Args> for Box<'m, F>
+where
+    F: ?Sized + FnMut<Args> + DynTrait,
+{
+    type Output = F::Output;
+
+    #[inline]
+    extern "rust-call" fn call_once(mut self, args: Args) -> Self::Output {
+        self.as_mut().call_mut(args)
+    }
+}
+
+impl<'m, Args, F> FnMut<Args> for Box<'m, F>
+where
+    F: ?Sized + FnMut<Args> + DynTrait,
+{
+    #[inline]
+    extern "rust-call" fn call_mut(&mut self, args: Args) -> Self::Output {
+        self.as_mut().call_mut(args)
+    }
+}
+
+impl<'m, Args, F> Fn<Args> for Box<'m, F>
+where
+    F: ?Sized + Fn<Args> + DynTrait,
+{
+    #[inline]
+    extern "rust-call" fn call(&self, args: Args) -> Self::Output {
+        self.as_ref().call(args)
+    }
+}
+
+impl<'m, H> Hasher for Box<'m, H>
+where
+    H: ?Sized + Hasher + DynTrait,
+{
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.as_ref().finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.as_mut().write(bytes)
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.as_mut().write_u8(i)
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.as_mut().write_u16(i)
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.as_mut().write_u32(i)
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.as_mut().write_u64(i)
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.as_mut().write_u128(i)
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.as_mut().write_usize(i)
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.as_mut().write_i8(i)
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.as_mut().write_i16(i)
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.as_mut().write_i32(i)
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.as_mut().write_i64(i)
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.as_mut().write_i128(i)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.as_mut().write_isize(i)
+    }
+}
+
 impl<'m, T> AsRef<T> for Box<'m, T>
 where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
+    T: ?Sized + DynTrait,
 {
     #[inline]
     fn as_ref(&self) -> &T {
@@ -258,7 +1717,7 @@ where
 
 impl<'m, T> AsMut<T> for Box<'m, T>
 where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
+    T: ?Sized + DynTrait,
 {
     #[inline]
     fn as_mut(&mut self) -> &mut T {
@@ -268,7 +1727,7 @@ where
 
 impl<'m, T> Deref for Box<'m, T>
 where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
+    T: ?Sized + DynTrait,
 {
     type Target = T;
 
@@ -280,7 +1739,7 @@ where
 
 impl<'m, T> DerefMut for Box<'m, T>
 where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
+    T: ?Sized + DynTrait,
 {
     #[inline]
     fn deref_mut(&mut self) -> &mut T {
@@ -288,14 +1747,39 @@ where
     }
 }
 
+impl<'a, 'm, T> IntoIterator for &'a Box<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    type Item = &'a T;
+    type IntoIter = core::iter::Once<&'a T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        core::iter::once(self.as_ref())
+    }
+}
+
+impl<'m, T> core::fmt::Pointer for Box<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Pointer::fmt(&self.value_ptr(), f)
+    }
+}
+
 impl<'m, T> Drop for Box<'m, T>
 where
-    T: ?Sized + Pointee<Metadata = DynMetadata<T>>,
+    T: ?Sized + DynTrait,
 {
     #[inline]
     fn drop(&mut self) {
         unsafe {
             ptr::drop_in_place::<T>(&mut **self);
         }
+        if let Some(hook) = self.drop_hook {
+            hook();
+        }
     }
 }
@@ -0,0 +1,82 @@
+use core::mem::ManuallyDrop;
+
+use crate::{Box, DynTrait};
+
+/// A box whose value is dropped only by an explicit [`drop_value`](Self::drop_value) call.
+///
+/// Dropping a `ManualBox` itself doesn't run the destructor of the stored value, so if
+/// `drop_value` is never called, the value is leaked in the buffer, just like with
+/// [`core::mem::forget`].
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// let mut value = Box::<dyn Display>::new(&mut mem, 42).into_manual();
+/// assert_eq!(value.get().unwrap().to_string(), "42");
+///
+/// value.drop_value();
+/// assert!(value.get().is_none());
+/// ```
+pub struct ManualBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    inner: ManuallyDrop<Box<'m, T>>,
+    live: bool,
+}
+
+impl<'m, T> ManualBox<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    pub(crate) fn new(inner: Box<'m, T>) -> Self {
+        Self {
+            inner: ManuallyDrop::new(inner),
+            live: true,
+        }
+    }
+
+    /// Runs the destructor of the stored value.
+    ///
+    /// The value is dropped only once, the subsequent calls do nothing.
+    #[inline]
+    pub fn drop_value(&mut self) {
+        if self.live {
+            // Mark the value as dropped before the drop, so a panicking destructor
+            // doesn't lead to a double drop.
+            self.live = false;
+            unsafe { ManuallyDrop::drop(&mut self.inner) };
+        }
+    }
+
+    /// Returns `true` if the stored value hasn't been dropped yet.
+    #[inline]
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// Returns a reference to the stored value, or `None` if it has been dropped.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        if self.live {
+            Some(self.inner.as_ref())
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the stored value, or `None` if it has been dropped.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.live {
+            Some(self.inner.as_mut())
+        } else {
+            None
+        }
+    }
+}
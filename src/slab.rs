@@ -0,0 +1,231 @@
+use core::{
+    cell::Cell,
+    marker::{PhantomData, Unsize},
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+use crate::{Box, DynTrait};
+
+/// The index of the free list terminator.
+const NONE: usize = usize::MAX;
+
+/// A slab of fixed size slots in the provided memory, each of them can store a dynamic object.
+///
+/// The free slots are linked into an intrusive list: the index of the next free slot is stored
+/// in the first bytes of the slot itself. Each [`SlabBox`] returns its slot to the list when
+/// it is dropped, so the slot can be reused by the next allocation.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::Slab;
+///
+/// let mut mem = [0_u8; 64];
+/// let slab = Slab::<dyn Display>::new(&mut mem, 32);
+///
+/// let first = slab.alloc(1).ok().unwrap();
+/// let second = slab.alloc('x').ok().unwrap();
+/// assert!(slab.alloc(3).is_err());
+///
+/// drop(first);
+/// let third = slab.alloc(3).ok().unwrap();
+/// assert_eq!(format!("{} {}", second.as_ref(), third.as_ref()), "x 3");
+/// ```
+pub struct Slab<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    mem: *mut u8,
+    slot_len: usize,
+    capacity: usize,
+    free_head: Cell<usize>,
+    allocated: Cell<usize>,
+    phantom: PhantomData<(&'m mut [u8], T)>,
+}
+
+impl<'m, T> Slab<'m, T>
+where
+    T: ?Sized + DynTrait,
+{
+    /// Splits the specified `mem` buffer into slots of the `slot_len` bytes. The trailing
+    /// bytes that don't make up a whole slot are not used.
+    ///
+    /// # Panics
+    ///
+    /// - If the `slot_len` is less than the size of `usize`, which is needed to link
+    ///   the free slots.
+    pub fn new(mem: &'m mut [u8], slot_len: usize) -> Self {
+        assert!(
+            slot_len >= mem::size_of::<usize>(),
+            "The slot length must be at least {} bytes",
+            mem::size_of::<usize>()
+        );
+
+        let capacity = mem.len() / slot_len;
+        let slab = Self {
+            mem: mem.as_mut_ptr(),
+            slot_len,
+            capacity,
+            free_head: Cell::new(if capacity == 0 { NONE } else { 0 }),
+            allocated: Cell::new(0),
+            phantom: PhantomData,
+        };
+        for index in 0..capacity {
+            let next = if index + 1 == capacity {
+                NONE
+            } else {
+                index + 1
+            };
+            unsafe { slab.write_link(index, next) };
+        }
+        slab
+    }
+
+    /// Places a `value` into a free slot.
+    ///
+    /// If there are no free slots, the value is returned back.
+    ///
+    /// # Panics
+    ///
+    /// - If the slot is insufficient to store the value.
+    pub fn alloc<Value>(&self, value: Value) -> Result<SlabBox<'_, T>, Value>
+    where
+        Value: Unsize<T>,
+    {
+        let index = self.free_head.get();
+        if index == NONE {
+            return Err(value);
+        }
+
+        // The slot is unlinked before the value overwrites the link.
+        self.free_head.set(unsafe { self.read_link(index) });
+        // Each slot is handed out only once until it is returned to the free list.
+        let slot = unsafe { slice::from_raw_parts_mut(self.slot_ptr(index), self.slot_len) };
+        let inner = match Box::try_new(slot, value) {
+            Ok(inner) => inner,
+            Err((_, err)) => {
+                self.release(index);
+                panic!("{}", err);
+            }
+        };
+        self.allocated.set(self.allocated.get() + 1);
+
+        Ok(SlabBox {
+            inner: ManuallyDrop::new(inner),
+            slab: self,
+            index,
+        })
+    }
+
+    /// Returns the total number of slots.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of the currently allocated slots.
+    #[inline]
+    pub fn allocated(&self) -> usize {
+        self.allocated.get()
+    }
+
+    fn release(&self, index: usize) {
+        unsafe { self.write_link(index, self.free_head.get()) };
+        self.free_head.set(index);
+    }
+
+    #[inline]
+    fn slot_ptr(&self, index: usize) -> *mut u8 {
+        unsafe { self.mem.add(index * self.slot_len) }
+    }
+
+    // The slot must be free.
+    #[inline]
+    unsafe fn read_link(&self, index: usize) -> usize {
+        self.slot_ptr(index).cast::<usize>().read_unaligned()
+    }
+
+    // The slot must be free.
+    #[inline]
+    unsafe fn write_link(&self, index: usize, next: usize) {
+        self.slot_ptr(index).cast::<usize>().write_unaligned(next)
+    }
+}
+
+/// A box stored in a slot of the [`Slab`], the slot is returned to the slab when the box
+/// is dropped.
+pub struct SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    inner: ManuallyDrop<Box<'s, T>>,
+    slab: &'s Slab<'s, T>,
+    index: usize,
+}
+
+impl<'s, T> SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    /// Returns the index of the slot occupied by the box.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'s, T> AsRef<T> for SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self.inner.as_ref()
+    }
+}
+
+impl<'s, T> AsMut<T> for SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        self.inner.as_mut()
+    }
+}
+
+impl<'s, T> Deref for SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<'s, T> DerefMut for SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.as_mut()
+    }
+}
+
+impl<'s, T> Drop for SlabBox<'s, T>
+where
+    T: ?Sized + DynTrait,
+{
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.inner) };
+        self.slab.allocated.set(self.slab.allocated.get() - 1);
+        self.slab.release(self.index);
+    }
+}
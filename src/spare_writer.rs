@@ -0,0 +1,64 @@
+use core::{fmt, str};
+
+/// A [`fmt::Write`] sink over the unused tail of the buffer of a box.
+///
+/// Created by the [`Box::spare_writer`](crate::Box::spare_writer) method. The text that
+/// doesn't fit into the spare bytes is rejected with [`fmt::Error`], the already written
+/// text is kept.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::{Display, Write};
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// let mut value = Box::<dyn Display>::new(&mut mem, 42_u8);
+///
+/// let mut writer = value.spare_writer();
+/// write!(writer, "value: {}", 42).unwrap();
+/// assert_eq!(writer.as_str(), "value: 42");
+/// ```
+pub struct SpareWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SpareWriter<'a> {
+    #[inline]
+    pub(crate) fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Returns the text written so far.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // Only whole strings are written into the buffer.
+        unsafe { str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// Returns the number of spare bytes that are still available for writing.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    /// Forgets the written text, so the spare bytes can be reused.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<'a> fmt::Write for SpareWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > self.remaining() {
+            return Err(fmt::Error);
+        }
+
+        let end = self.len + s.len();
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use core::{
+    cell::UnsafeCell,
+    marker::Unsize,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use crate::{Box, DynTrait};
+
+const UNINIT: u8 = 0;
+const INITIALIZING: u8 = 1;
+const READY: u8 = 2;
+
+/// A cell with its own buffer that can be declared as a regular `static` item and
+/// initialized with a dynamic object only once.
+///
+/// This is a sound alternative to the `static mut` pairs of a buffer and an optional box.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::StaticBox;
+///
+/// static VALUE: StaticBox<dyn Display + Send + Sync, 32> = StaticBox::new();
+///
+/// assert!(VALUE.get().is_none());
+/// assert_eq!(VALUE.init(42).unwrap().to_string(), "42");
+/// assert_eq!(VALUE.get().unwrap().to_string(), "42");
+/// assert!(VALUE.init(43).is_err());
+/// ```
+pub struct StaticBox<T, const N: usize>
+where
+    T: ?Sized + DynTrait,
+{
+    state: AtomicU8,
+    mem: UnsafeCell<[u8; N]>,
+    value: UnsafeCell<Option<Box<'static, T>>>,
+}
+
+// The stored value is written only once by the thread that wins the `init` race, and after
+// that it is accessible only through shared references.
+unsafe impl<T, const N: usize> Sync for StaticBox<T, N> where T: ?Sized + DynTrait + Send + Sync {}
+
+impl<T, const N: usize> StaticBox<T, N>
+where
+    T: ?Sized + DynTrait,
+{
+    /// Creates a new empty cell with a zeroed buffer.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(UNINIT),
+            mem: UnsafeCell::new([0; N]),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Places a `value` into the buffer of the cell and returns a reference to it.
+    ///
+    /// If the cell has already been initialized, or is being initialized by another thread,
+    /// the value is returned back.
+    ///
+    /// # Panics
+    ///
+    /// - If the buffer is insufficient to store the value.
+    pub fn init<Value>(&'static self, value: Value) -> Result<&'static T, Value>
+    where
+        Value: Unsize<T>,
+    {
+        if self
+            .state
+            .compare_exchange(UNINIT, INITIALIZING, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(value);
+        }
+
+        // The state guarantees that the buffer is borrowed only once.
+        let mem = unsafe { &mut *self.mem.get() };
+        let value = match Box::try_new(mem, value) {
+            Ok(value) => value,
+            Err((_, err)) => {
+                self.state.store(UNINIT, Ordering::Release);
+                panic!("{}", err);
+            }
+        };
+        unsafe { *self.value.get() = Some(value) };
+        self.state.store(READY, Ordering::Release);
+
+        Ok(self.get().unwrap())
+    }
+
+    /// Returns a reference to the stored value, or `None` if the cell hasn't been
+    /// initialized yet.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) == READY {
+            // The value is never changed after the initialization.
+            unsafe { (*self.value.get()).as_deref() }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the stored value, or `None` if the cell hasn't been
+    /// initialized yet.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if *self.state.get_mut() == READY {
+            self.value.get_mut().as_deref_mut()
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N: usize> Default for StaticBox<T, N>
+where
+    T: ?Sized + DynTrait,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
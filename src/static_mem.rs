@@ -0,0 +1,61 @@
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A buffer that can be declared as a regular `static` item and mutably borrowed only once.
+///
+/// This is a sound alternative to the `static mut` buffers, the buffer is handed out
+/// by the [`take`](Self::take) method at most once for the whole program.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::{Box, StaticMem};
+///
+/// static MEM: StaticMem<32> = StaticMem::new();
+///
+/// let value = Box::<dyn Display>::new_in_static(&MEM, 42);
+/// assert_eq!(value.to_string(), "42");
+/// assert!(MEM.take().is_none());
+/// ```
+pub struct StaticMem<const N: usize> {
+    taken: AtomicBool,
+    mem: UnsafeCell<[u8; N]>,
+}
+
+// The buffer itself is accessible only through the unique reference returned by the `take`
+// method, so the shared references to `StaticMem` can be safely used from multiple threads.
+unsafe impl<const N: usize> Sync for StaticMem<N> {}
+
+impl<const N: usize> StaticMem<N> {
+    /// Creates a new zeroed buffer.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            taken: AtomicBool::new(false),
+            mem: UnsafeCell::new([0; N]),
+        }
+    }
+
+    /// Returns the unique reference to the buffer, or `None` if the buffer has already
+    /// been taken.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub fn take(&self) -> Option<&mut [u8]> {
+        if self.taken.swap(true, Ordering::AcqRel) {
+            None
+        } else {
+            // The flag guarantees that this reference is created only once.
+            Some(unsafe { &mut *self.mem.get() })
+        }
+    }
+}
+
+impl<const N: usize> Default for StaticMem<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,24 @@
 use std::{
-    fmt::{Debug, Display},
+    any::{Any, TypeId},
+    cell::Cell,
+    collections::hash_map::DefaultHasher,
+    fmt::{Debug, Display, Write},
+    hash::{Hash, Hasher},
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr::NonNull,
     sync::mpsc,
+    thread,
 };
 
-use crate::Box;
+use crate::{
+    align_up, padding_needed, Aligned, Box, InsufficientMemory, OutOfRegion, Reason, Slab,
+    StaticBox, StaticMem, TypedBox, TypedSlot, A16, A8,
+};
+
+// The bytes must be initialized.
+unsafe fn assume_init(bytes: &[MaybeUninit<u8>]) -> Vec<u8> {
+    bytes.iter().map(|byte| byte.assume_init()).collect()
+}
 
 #[test]
 fn test_box_trait_object() {
@@ -136,3 +151,1803 @@ fn test_box_in_static_mem() {
         assert_eq!(BOX.as_ref().unwrap().to_string(), "42");
     }
 }
+
+#[test]
+fn test_box_swap() {
+    let mut first_mem = [0_u8; 64];
+    let mut second_mem = [0_u8; 64];
+
+    let mut first = Box::<dyn Display>::new(&mut first_mem[1..], 42_u64);
+    let mut second = Box::<dyn Display>::new(&mut second_mem, 'x');
+    first.swap(&mut second);
+    assert_eq!(first.to_string(), "x");
+    assert_eq!(second.to_string(), "42");
+
+    second.swap(&mut first);
+    assert_eq!(first.to_string(), "42");
+    assert_eq!(second.to_string(), "x");
+}
+
+#[test]
+#[should_panic(expected = "Not enough memory to swap")]
+fn test_box_swap_insufficient_memory() {
+    let mut first_mem = [0_u8; 64];
+    let mut second_mem = [0_u8; 16];
+
+    let mut first = Box::<dyn Display>::new(&mut first_mem, "long string slice");
+    let mut second = Box::<dyn Display>::new(&mut second_mem, 1_u8);
+    first.swap(&mut second);
+}
+
+#[test]
+fn test_box_any_send_downcast() {
+    let mem = std::boxed::Box::leak(vec![0_u8; 64].into_boxed_slice());
+    let message = Box::<dyn Any + Send>::new(mem, String::from("ping"));
+
+    let handle = thread::spawn(move || {
+        let message = message.downcast_send::<u32>().err().unwrap();
+        message.downcast_send::<String>().ok().unwrap()
+    });
+    assert_eq!(handle.join().unwrap(), "ping");
+}
+
+#[test]
+fn test_box_as_ref_with_meta() {
+    let mut mem = [0_u8; 64];
+    let value = Box::<dyn Display>::new(&mut mem[1..], 42_u64);
+
+    let value_offset = value.value_offset();
+    let meta = value.metadata();
+    for _ in 0..3 {
+        let cached = unsafe { value.as_ref_with_meta(value_offset, meta) };
+        assert_eq!(cached.to_string(), value.as_ref().to_string());
+        assert_eq!(
+            cached as *const dyn Display as *const u8,
+            value.as_ref() as *const dyn Display as *const u8
+        );
+    }
+}
+
+#[test]
+fn test_box_non_static_closure() {
+    let value = String::from("borrowed");
+
+    let mut mem = [0_u8; 32];
+    let len = Box::<dyn Fn() -> usize + '_>::new(&mut mem, || value.len());
+    assert_eq!(len(), 8);
+}
+
+#[test]
+fn test_capacity_for() {
+    const LEN: usize = Box::<dyn Display>::capacity_for::<u64>();
+
+    let value = 42_u64;
+    let layout = Box::<dyn Display>::layout_of_dyn(&value);
+    assert_eq!(LEN, layout.size() + layout.align() - 1);
+
+    let mut mem = [0_u8; LEN + 1];
+    for offset in 0..2 {
+        let val = Box::<dyn Display>::new(&mut mem[offset..offset + LEN], value);
+        assert_eq!(val.to_string(), "42");
+    }
+}
+
+#[test]
+fn test_boxed_slice_of_closures() {
+    let mut mem = [0_u8; 64];
+    let closures =
+        Box::<dyn Fn() -> usize>::new_boxed_slice(&mut mem[1..], (0..4).map(|i| move || i * 10));
+    assert_eq!(closures.len(), 4);
+    for i in 0..4 {
+        assert_eq!(closures[i](), i * 10);
+    }
+    assert_eq!(closures.iter().map(|f| f()).sum::<usize>(), 60);
+    assert!(closures.get(4).is_none());
+}
+
+#[test]
+fn test_boxed_slice_mutation_and_drop() {
+    trait Renumber: Debug {
+        fn renumber(&mut self, id: i32);
+    }
+
+    #[derive(Debug)]
+    struct Foo {
+        id: i32,
+        tx: mpsc::Sender<i32>,
+    }
+
+    impl Renumber for Foo {
+        fn renumber(&mut self, id: i32) {
+            self.id = id;
+        }
+    }
+
+    impl Drop for Foo {
+        fn drop(&mut self) {
+            self.tx.send(self.id).unwrap();
+        }
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut mem = [0_u8; 256];
+    let mut slice =
+        Box::<dyn Renumber>::new_boxed_slice(&mut mem, (0..3).map(|id| Foo { id, tx: tx.clone() }));
+    drop(tx);
+
+    for (id, value) in slice.iter_mut().enumerate() {
+        value.renumber(10 + id as i32);
+    }
+    slice[1].renumber(100);
+    assert!(format!("{:?}", &slice[0]).starts_with("Foo { id: 10,"));
+    assert!(format!("{:?}", &slice[1]).starts_with("Foo { id: 100,"));
+    assert!(format!("{:?}", &slice[2]).starts_with("Foo { id: 12,"));
+    // Nothing is dropped while the slice is alive.
+    assert!(rx.try_recv().is_err());
+    drop(slice);
+
+    let mut dropped = rx.iter().collect::<Vec<_>>();
+    dropped.sort_unstable();
+    assert_eq!(dropped, [10, 12, 100]);
+}
+
+#[test]
+fn test_boxed_slice_index_mut() {
+    let mut mem = [0_u8; 64];
+    let mut counters = Box::<dyn FnMut() -> usize>::new_boxed_slice(
+        &mut mem,
+        (0..2).map(|_| {
+            let mut counter = 0;
+            move || {
+                counter += 1;
+                counter
+            }
+        }),
+    );
+    assert_eq!(counters[0](), 1);
+    assert_eq!(counters[0](), 2);
+    assert_eq!(counters[1](), 1);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 2 but the index is 2")]
+fn test_boxed_slice_index_out_of_bounds() {
+    let mut mem = [0_u8; 64];
+    let values = Box::<dyn Display>::new_boxed_slice(&mut mem, vec![1, 2]);
+    let _ = values[2].to_string();
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 0 but the index is 0")]
+fn test_boxed_slice_index_mut_out_of_bounds() {
+    let mut mem = [0_u8; 64];
+    let mut values = Box::<dyn FnMut()>::new_boxed_slice(&mut mem, std::iter::empty::<fn()>());
+    values[0]();
+}
+
+#[test]
+fn test_box_try_new() {
+    let value = 42_u64;
+    let layout = Box::<dyn Display>::layout_of_dyn(&value);
+
+    let mut mem = [0_u8; 64];
+    let align_offset = mem.as_ptr().align_offset(layout.align());
+    let needed = align_offset + layout.size();
+
+    let (value, err) = Box::<dyn Display>::try_new(&mut mem[..needed - 1], value)
+        .err()
+        .unwrap();
+    assert_eq!(value, 42);
+    assert_eq!(
+        err,
+        InsufficientMemory {
+            available: needed - 1,
+            needed,
+            type_name: "u64",
+            reason: if align_offset == 0 {
+                Reason::Size
+            } else {
+                Reason::Alignment
+            },
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Not enough memory to store the specified value of type `u64` (got: {}, needed: {})",
+            needed - 1,
+            needed
+        )
+    );
+    assert!(mem.iter().all(|&byte| byte == 0));
+
+    let val = Box::<dyn Display>::try_new(&mut mem[..needed], value)
+        .ok()
+        .unwrap();
+    assert_eq!(val.to_string(), "42");
+}
+
+#[test]
+fn test_layout_of() {
+    assert_eq!(
+        Box::<dyn Display>::layout_of::<u64>(),
+        Box::<dyn Display>::layout_of_dyn(&42_u64)
+    );
+    assert_eq!(
+        Box::<dyn Display>::layout_of::<u8>(),
+        Box::<dyn Display>::layout_of_dyn(&42_u8)
+    );
+    assert_eq!(
+        Box::<dyn Debug>::layout_of::<[u16; 3]>(),
+        Box::<dyn Debug>::layout_of_dyn(&[1_u16, 2, 3])
+    );
+}
+
+#[test]
+fn test_typed_slot_place_and_clear() {
+    #[derive(Debug)]
+    struct Counter<'a> {
+        id: usize,
+        drops: &'a Cell<usize>,
+    }
+
+    impl Drop for Counter<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut mem = [0_u8; 64];
+    let mut slot = TypedSlot::<dyn Debug + '_, Counter>::new(&mut mem[1..]);
+    assert!(slot.get().is_none());
+
+    for id in 0..1000 {
+        slot.place(Counter { id, drops: &drops });
+        assert!(slot.is_occupied());
+        assert!(format!("{:?}", slot.get().unwrap()).contains(&format!("id: {},", id)));
+        slot.clear();
+        assert!(slot.get_mut().is_none());
+    }
+    assert_eq!(drops.get(), 1000);
+
+    // Placing into the occupied slot drops the previous value.
+    slot.place(Counter {
+        id: 1,
+        drops: &drops,
+    });
+    slot.place(Counter {
+        id: 2,
+        drops: &drops,
+    });
+    assert_eq!(drops.get(), 1001);
+
+    let boxed = slot.into_box().unwrap();
+    assert!(format!("{:?}", boxed.as_ref()).contains("id: 2"));
+    drop(boxed);
+    assert_eq!(drops.get(), 1002);
+}
+
+#[test]
+fn test_box_shrink_buffer() {
+    let mut mem = [0_u8; 64];
+    let value = 42_u64;
+
+    let layout = Box::<dyn Display>::layout_of_dyn(&value);
+    let align_offset = mem.as_ptr().align_offset(layout.align());
+
+    let mut first = Box::<dyn Display>::new(&mut mem, value);
+    assert_eq!(first.used(), align_offset + layout.size());
+
+    let tail = first.shrink_buffer();
+    assert_eq!(tail.len(), 64 - align_offset - layout.size());
+    assert!(first.shrink_buffer().is_empty());
+
+    let second = Box::<dyn Display>::new(tail, 7_u8);
+    assert_eq!(first.to_string(), "42");
+    assert_eq!(second.to_string(), "7");
+}
+
+#[test]
+fn test_manual_box_drop_timing() {
+    #[derive(Debug)]
+    struct Foo<'a> {
+        drops: &'a Cell<usize>,
+    }
+
+    impl Drop for Foo<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut mem = [0_u8; 32];
+    let mut value = Box::<dyn Debug + '_>::new(&mut mem, Foo { drops: &drops }).into_manual();
+    assert!(value.is_live());
+    assert!(value.get_mut().is_some());
+    assert_eq!(drops.get(), 0);
+
+    value.drop_value();
+    assert!(!value.is_live());
+    assert!(value.get().is_none());
+    assert_eq!(drops.get(), 1);
+
+    value.drop_value();
+    drop(value);
+    assert_eq!(drops.get(), 1);
+
+    // Without an explicit call the value is never dropped.
+    let value = Box::<dyn Debug + '_>::new(&mut mem, Foo { drops: &drops }).into_manual();
+    drop(value);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_box_scoped_reuses_buffer() {
+    let (tx, rx) = mpsc::channel();
+    let mut mem = [0_u8; 32];
+
+    let len = Box::<dyn FnMut(&str) -> usize>::scoped(
+        &mut mem,
+        move |s: &str| {
+            tx.send(s.to_owned()).unwrap();
+            s.len()
+        },
+        |send| send("first") + send("second"),
+    );
+    assert_eq!(len, 11);
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), ["first", "second"]);
+    // The closure with the sender has already been dropped.
+    assert!(rx.recv().is_err());
+
+    let value = Box::<dyn Display>::scoped(&mut mem, 42_u64, |value| value.to_string());
+    assert_eq!(value, "42");
+}
+
+#[test]
+fn test_box_new_within_region() {
+    let mut sram = [0_u8; 128];
+    let region = sram.as_ptr_range();
+
+    let val = Box::<dyn Display>::new_within(region.clone(), &mut sram[32..96], 42)
+        .ok()
+        .unwrap();
+    assert_eq!(val.to_string(), "42");
+    drop(val);
+
+    let mut other = [0_u8; 64];
+    let (value, err) = Box::<dyn Display>::new_within(region, &mut other, 42)
+        .err()
+        .unwrap();
+    assert_eq!(value, 42);
+    assert_eq!(err, OutOfRegion);
+
+    // A buffer that partially overlaps the region is rejected as well.
+    let region = sram[..64].as_ptr_range();
+    assert!(Box::<dyn Display>::new_within(region, &mut sram[32..96], 42).is_err());
+}
+
+#[test]
+fn test_box_dyn_fn_generic_bound() {
+    fn call_twice<F: Fn(i32) -> i32>(f: F) -> i32 {
+        f(1) + f(2)
+    }
+
+    fn call_mut_twice<F: FnMut() -> usize>(mut f: F) -> usize {
+        f();
+        f()
+    }
+
+    fn call_once<F: FnOnce() -> String>(f: F) -> String {
+        f()
+    }
+
+    let mut mem = [0_u8; 32];
+    let add = Box::<dyn Fn(i32) -> i32>::new(&mut mem, |a| a + 10);
+    assert_eq!(call_twice(&add), 23);
+    assert_eq!(call_twice(add), 23);
+
+    let mut counter = 0;
+    let increment = Box::<dyn FnMut() -> usize>::new(&mut mem, move || {
+        counter += 1;
+        counter
+    });
+    assert_eq!(call_mut_twice(increment), 2);
+
+    let greeting = String::from("Hello");
+    let greet = Box::<dyn FnMut() -> String>::new(&mut mem, move || greeting.clone());
+    assert_eq!(call_once(greet), "Hello");
+}
+
+#[test]
+fn test_box_in_static_mem_cell() {
+    static MEM: StaticMem<32> = StaticMem::new();
+
+    fn global_display() -> Box<'static, dyn Display> {
+        Box::<dyn Display>::new_in_static(&MEM, 42)
+    }
+
+    let value = global_display();
+    assert_eq!(value.to_string(), "42");
+    assert!(MEM.take().is_none());
+}
+
+#[test]
+#[should_panic(expected = "The static buffer has already been taken")]
+fn test_box_in_static_mem_cell_taken() {
+    static MEM: StaticMem<32> = StaticMem::new();
+
+    let _mem = MEM.take().unwrap();
+    let _value = Box::<dyn Display>::new_in_static(&MEM, 42);
+}
+
+#[test]
+fn test_align_helpers() {
+    for &align in &[1, 2, 4, 8, 16, 4096] {
+        assert_eq!(align_up(0, align), 0);
+        assert_eq!(padding_needed(0, align), 0);
+        assert_eq!(align_up(align, align), align);
+        assert_eq!(padding_needed(align, align), 0);
+        assert_eq!(
+            align_up(align + 1, align),
+            if align == 1 { 2 } else { 2 * align }
+        );
+
+        for addr in 0..64 {
+            let aligned = align_up(addr, align);
+            assert_eq!(aligned % align, 0);
+            assert!(aligned >= addr && aligned - addr < align);
+            assert_eq!(padding_needed(addr, align), aligned - addr);
+        }
+    }
+
+    // Edge cases near the upper bound of `usize`.
+    assert_eq!(padding_needed(usize::MAX, 1), 0);
+    assert_eq!(padding_needed(usize::MAX, 8), 1);
+    assert_eq!(align_up(usize::MAX - 7, 8), usize::MAX - 7);
+
+    // The helpers agree with the actual placement.
+    let mut mem = [0_u8; 64];
+    let layout = Box::<dyn Display>::layout_of::<u64>();
+    let align_offset = padding_needed(mem[1..].as_ptr() as usize, layout.align());
+    let val = Box::<dyn Display>::new(&mut mem[1..], 42_u64);
+    assert_eq!(val.used(), align_offset + layout.size());
+}
+
+#[test]
+fn test_box_validate() {
+    let mut mem = [0_u8; 64];
+    let val = Box::<dyn Display>::new(&mut mem[1..], 42_u64);
+    assert!(unsafe { val.validate() });
+    drop(val);
+
+    // The vtable pointer must be null, misaligned or readable.
+    unsafe fn corrupted_box(mem: &mut [u8], vtable_addr: usize) -> bool {
+        let align_offset = padding_needed(mem.as_ptr() as usize, std::mem::align_of::<usize>());
+        mem[align_offset..align_offset + std::mem::size_of::<usize>()]
+            .copy_from_slice(&vtable_addr.to_ne_bytes());
+
+        // Rehydrate the box from the corrupted buffer and never drop it.
+        let corrupted = ManuallyDrop::new(Box::<dyn Display>::from_raw_parts(mem, align_offset));
+        corrupted.validate()
+    }
+
+    unsafe {
+        // A zeroed metadata.
+        assert!(!corrupted_box(&mut mem, 0));
+        // A misaligned vtable pointer is not dereferenced.
+        assert!(!corrupted_box(&mut mem, 0x1001));
+
+        // Fake vtables in the `[drop_in_place, size, align]` form.
+        static BAD_ALIGN: [usize; 3] = [0, 8, 3];
+        static TOO_BIG: [usize; 3] = [0, 1000, 8];
+        static PLAUSIBLE: [usize; 3] = [0, 8, 8];
+        assert!(!corrupted_box(&mut mem, BAD_ALIGN.as_ptr() as usize));
+        assert!(!corrupted_box(&mut mem, TOO_BIG.as_ptr() as usize));
+        assert!(corrupted_box(&mut mem, PLAUSIBLE.as_ptr() as usize));
+    }
+
+    // A buffer that is too short even for the metadata.
+    let short = ManuallyDrop::new(Box::<dyn Display>::from_raw_parts(&mut mem[..4], 0));
+    assert!(!unsafe { short.validate() });
+}
+
+#[test]
+fn test_box_try_new_uninit() {
+    let mut mem = [MaybeUninit::<u8>::uninit(); 64];
+
+    let mut val = Box::<dyn Display>::try_new_uninit(&mut mem[1..], 42_u64)
+        .ok()
+        .unwrap();
+    assert_eq!(val.to_string(), "42");
+    assert!(val.shrink_buffer().is_empty());
+    drop(val);
+
+    let (value, err) = Box::<dyn Display>::try_new_uninit(&mut mem[..4], String::from("value"))
+        .err()
+        .unwrap();
+    assert_eq!(value, "value");
+    assert_eq!(err.available, 4);
+    assert!(err.needed > 4);
+}
+
+#[test]
+fn test_box_meta_and_value_bytes() {
+    let mut mem = [0_u8; 64];
+
+    let val = Box::<dyn Display>::new(&mut mem[1..], 0x0102_0304_0506_0708_u64);
+    let meta = val.meta_bytes().as_ptr_range();
+    let value = val.value_bytes().as_ptr_range();
+    assert!(meta.end <= value.start.cast());
+    assert_eq!(
+        val.meta_bytes().len() + val.value_bytes().len(),
+        val.used() - val.align_offset
+    );
+    assert_eq!(
+        unsafe { assume_init(val.value_bytes()) },
+        0x0102_0304_0506_0708_u64.to_ne_bytes()
+    );
+    drop(val);
+
+    let val = Box::<dyn Display>::new(&mut mem, 7_u8);
+    assert_eq!(unsafe { assume_init(val.value_bytes()) }, [7]);
+    assert_eq!(
+        val.meta_bytes().len() + val.value_bytes().len(),
+        val.used() - val.align_offset
+    );
+}
+
+#[test]
+fn test_box_dyn_send_sync() {
+    fn assert_send_sync<S: Send + Sync>(_: &S) {}
+
+    let mut mem = [0_u8; 32];
+    let val = Box::<dyn Display + Send + Sync>::new(&mut mem, 42_u64);
+    assert_send_sync(&val);
+    assert_eq!(val.to_string(), "42");
+    drop(val);
+
+    let mem = std::boxed::Box::leak(vec![0_u8; 64].into_boxed_slice());
+    let val = Box::<dyn Display + Send + Sync>::new(mem, String::from("shared"));
+    let val = thread::spawn(move || {
+        assert_eq!(val.to_string(), "shared");
+        val
+    })
+    .join()
+    .unwrap();
+    assert_eq!(val.to_string(), "shared");
+}
+
+#[test]
+fn test_box_try_from_fn() {
+    #[derive(Debug, PartialEq)]
+    struct InitError;
+
+    let mut mem = [0xAA_u8; 32];
+    let err = Box::<dyn Display>::try_from_fn(&mut mem, || Err::<u32, _>(InitError))
+        .err()
+        .unwrap();
+    assert_eq!(err, InitError);
+    assert!(mem.iter().all(|&byte| byte == 0xAA));
+
+    let val = Box::<dyn Display>::try_from_fn(&mut mem, || Ok::<_, InitError>(42_u32))
+        .ok()
+        .unwrap();
+    assert_eq!(val.to_string(), "42");
+}
+
+#[test]
+fn test_box_alignment_waste() {
+    #[repr(align(8))]
+    struct Aligned([u8; 64]);
+
+    let mut mem = Aligned([0; 64]);
+    let val = Box::<dyn Display>::new(&mut mem.0, 42_u64);
+    assert_eq!(val.alignment_waste(), 0);
+    drop(val);
+
+    let val = Box::<dyn Display>::new(&mut mem.0[3..], 42_u64);
+    assert_eq!(val.alignment_waste(), 5);
+    assert_eq!(
+        val.used(),
+        5 + Box::<dyn Display>::layout_of::<u64>().size()
+    );
+}
+
+#[test]
+fn test_box_new_reporting() {
+    let mut mem = [0_u8; 64];
+
+    let value = [1_u16, 2, 3];
+    let expected = Box::<dyn Debug>::layout_of_dyn(&value);
+    let (val, layout) = Box::<dyn Debug>::new_reporting(&mut mem[1..], value);
+    assert_eq!(layout, expected);
+    assert_eq!(val.used(), val.alignment_waste() + layout.size());
+    assert_eq!(format!("{:?}", val.as_ref()), "[1, 2, 3]");
+}
+
+#[test]
+fn test_box_new_with_layout() {
+    let layout = Box::<dyn Debug>::layout_of::<(u8, u32)>();
+
+    let mut expected_mem = [0_u8; 64];
+    let expected = Box::<dyn Debug>::new(&mut expected_mem[1..], (1_u8, 2_u32));
+
+    let mut mem = [0_u8; 64];
+    for i in 0..3_u32 {
+        let val = Box::<dyn Debug>::new_with_layout(&mut mem[1..], (1_u8, i), layout);
+        assert_eq!(format!("{:?}", val.as_ref()), format!("(1, {})", i));
+        assert_eq!(val.used(), expected.used());
+        assert_eq!(val.value_offset(), expected.value_offset());
+    }
+}
+
+#[test]
+#[should_panic(expected = "The provided layout doesn't match the value type")]
+fn test_box_new_with_layout_mismatch() {
+    let layout = Box::<dyn Debug>::layout_of::<u8>();
+
+    let mut mem = [0_u8; 64];
+    Box::<dyn Debug>::new_with_layout(&mut mem, 42_u64, layout);
+}
+
+#[test]
+fn test_box_freeze() {
+    let counter = Cell::new(0);
+    struct Counted<'a>(&'a Cell<u32>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    impl Display for Counted<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "dropped {} times", self.0.get())
+        }
+    }
+
+    let mut mem = [0_u8; 32];
+    let frozen: &dyn Display = Box::<dyn Display + '_>::new(&mut mem, Counted(&counter)).freeze();
+    let shared = frozen;
+    assert_eq!(frozen.to_string(), "dropped 0 times");
+    assert_eq!(shared.to_string(), "dropped 0 times");
+    assert_eq!(counter.get(), 0);
+}
+
+#[test]
+#[should_panic(expected = "of type `[u32; 4]`")]
+fn test_box_insufficient_memory_type_name() {
+    let mut mem = [0; 8];
+    let _array = Box::<dyn Debug>::new(&mut mem, [0_u32; 4]);
+}
+
+#[test]
+fn test_box_from_value_bytes() {
+    let mut mem = [0_u8; 64];
+    // The value has padding, so some of its bytes are uninitialized.
+    let val = Box::<dyn Debug>::new(&mut mem[1..], (1_u8, 2_u32));
+    let meta = val.metadata();
+    let mut bytes = [MaybeUninit::<u8>::uninit(); 8];
+    bytes.copy_from_slice(val.value_bytes());
+    drop(val);
+
+    let mut other_mem = [0_u8; 64];
+    let restored = unsafe {
+        Box::<dyn Debug>::from_value_bytes(&mut other_mem[3..], bytes.as_ptr(), bytes.len(), meta)
+    };
+    assert_eq!(format!("{:?}", restored.as_ref()), "(1, 2)");
+    assert_eq!(restored.value_bytes().len(), bytes.len());
+    assert!(unsafe { restored.validate() });
+}
+
+#[test]
+fn test_box_extern_c_methods() {
+    trait Device {
+        extern "C" fn read(&self, reg: u32) -> u32;
+        extern "C" fn write(&mut self, reg: u32, value: u32);
+    }
+
+    #[repr(C)]
+    struct Registers {
+        base: u32,
+        regs: [u32; 4],
+    }
+
+    impl Device for Registers {
+        extern "C" fn read(&self, reg: u32) -> u32 {
+            self.base + self.regs[reg as usize]
+        }
+
+        extern "C" fn write(&mut self, reg: u32, value: u32) {
+            self.regs[reg as usize] = value;
+        }
+    }
+
+    let mut mem = [0_u8; 64];
+    let mut device = Box::<dyn Device>::new(
+        &mut mem[1..],
+        Registers {
+            base: 0x100,
+            regs: [0; 4],
+        },
+    );
+    device.write(2, 0x42);
+    assert_eq!(device.read(2), 0x142);
+    assert_eq!(device.read(3), 0x100);
+
+    // The method of the trait object can also be called through a function pointer.
+    let read: extern "C" fn(&(dyn Device + 'static), u32) -> u32 = <dyn Device>::read;
+    assert_eq!(read(device.as_ref(), 2), 0x142);
+}
+
+#[test]
+fn test_box_trait_with_sized_only_methods() {
+    trait Shape {
+        fn area(&self) -> u32;
+
+        fn scaled(self, factor: u32) -> Self
+        where
+            Self: Sized;
+
+        fn describe(&self) -> String {
+            format!("area {}", self.area())
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Square(u32);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+
+        fn scaled(self, factor: u32) -> Self {
+            Square(self.0 * factor)
+        }
+    }
+
+    let mut mem = [0_u8; 32];
+    let shape = Box::<dyn Shape>::new(&mut mem, Square(2).scaled(3));
+    assert_eq!(shape.area(), 36);
+    assert_eq!(shape.describe(), "area 36");
+}
+
+#[test]
+fn test_box_new_aligned_to_buffer() {
+    let mut mem = Aligned::<A16, _>::new([0_u8; 48]);
+    let start = mem.as_ptr();
+    assert_eq!(start as usize % 16, 0);
+
+    let val = Box::<dyn Debug>::new_aligned_to_buffer(&mut mem, (1_u8, 2_u128));
+    assert_eq!(val.alignment_waste(), 0);
+    assert_eq!(val.meta_bytes().as_ptr(), start);
+    assert_eq!(format!("{:?}", val.as_ref()), "(1, 2)");
+    drop(val);
+
+    let val = Box::<dyn Display>::new_aligned_to_buffer(&mut mem, 'x');
+    assert_eq!(val.used(), Box::<dyn Display>::layout_of::<char>().size());
+    assert_eq!(val.to_string(), "x");
+}
+
+#[test]
+fn test_box_record_align() {
+    const ALIGN: usize = Box::<dyn Display>::record_align::<u8>();
+    assert_eq!(ALIGN, std::mem::align_of::<usize>());
+    assert_eq!(
+        Box::<dyn Debug>::record_align::<(u8, u64)>(),
+        Box::<dyn Debug>::layout_of::<(u8, u64)>().align()
+    );
+
+    // The record is at least pointer aligned due to the metadata.
+    let mut mem = Aligned::<A8, _>::new([0_u8; 32]);
+    Box::<dyn Debug>::assert_buffer_alignment::<[u8; 3], A8>();
+    let val = Box::<dyn Debug>::new_aligned_to_buffer(&mut mem, [1_u8, 2, 3]);
+    assert_eq!(format!("{:?}", val.as_ref()), "[1, 2, 3]");
+}
+
+#[test]
+fn test_box_default_methods_dispatch() {
+    trait Named {
+        fn name(&self) -> String;
+    }
+
+    trait Greeter: Named {
+        fn greeting(&self) -> &'static str {
+            "Hello"
+        }
+
+        fn greet(&self) -> String {
+            format!("{}, {}!", self.greeting(), self.name())
+        }
+    }
+
+    struct English;
+    struct French(&'static str);
+
+    impl Named for English {
+        fn name(&self) -> String {
+            "world".to_owned()
+        }
+    }
+
+    impl Greeter for English {}
+
+    impl Named for French {
+        fn name(&self) -> String {
+            self.0.to_owned()
+        }
+    }
+
+    impl Greeter for French {
+        fn greeting(&self) -> &'static str {
+            "Bonjour"
+        }
+    }
+
+    let mut english_mem = [0_u8; 32];
+    let mut french_mem = [0_u8; 32];
+    let greeters = [
+        Box::<dyn Greeter>::new(&mut english_mem, English),
+        Box::<dyn Greeter>::new(&mut french_mem[1..], French("le monde")),
+    ];
+    let greetings = greeters
+        .iter()
+        .map(|greeter| greeter.greet())
+        .collect::<Vec<_>>();
+    assert_eq!(greetings, ["Hello, world!", "Bonjour, le monde!"]);
+}
+
+#[test]
+fn test_box_with_encrypted_buffer() {
+    // The record of a `[u32; 3]` has neither gaps nor padding, so all its bytes
+    // are initialized.
+    unsafe fn xor_keystream(record: &mut [MaybeUninit<u8>]) {
+        for (i, byte) in record.iter_mut().enumerate() {
+            let key = (i as u8).wrapping_mul(31).wrapping_add(7);
+            *byte = MaybeUninit::new(byte.assume_init() ^ key);
+        }
+    }
+
+    let mut mem = [0_u8; 64];
+    let mut val = Box::<dyn Debug>::new(&mut mem[1..], [1_u32, 2, 3]);
+    let plain = val.meta_bytes().to_vec();
+
+    unsafe {
+        val.with_encrypted_buffer(|record| {
+            let expected_len = Box::<dyn Debug>::layout_of::<[u32; 3]>().size();
+            assert_eq!(record.len(), expected_len);
+
+            xor_keystream(record);
+            assert_ne!(assume_init(&record[..plain.len()]), plain);
+            xor_keystream(record);
+        })
+    };
+    assert_eq!(format!("{:?}", val.as_ref()), "[1, 2, 3]");
+}
+
+#[test]
+fn test_box_new_in_place_from_bytes() {
+    #[derive(Debug)]
+    #[repr(C)]
+    struct Packet {
+        id: u32,
+        payload: [u8; 12],
+    }
+
+    fn dma_receive(dst: &mut [u8]) {
+        dst[..4].copy_from_slice(&7_u32.to_ne_bytes());
+        dst[4..].copy_from_slice(b"hello, world");
+    }
+
+    let mut mem = [0_u8; 64];
+    let buf = &mut mem[3..];
+    assert!(Box::<dyn Debug>::value_offset_in::<Packet>(&buf[..20]).is_none());
+
+    let offset = Box::<dyn Debug>::value_offset_in::<Packet>(buf).unwrap();
+    let value_addr = buf[offset..].as_ptr();
+    dma_receive(&mut buf[offset..offset + 16]);
+
+    let packet = unsafe { Box::<dyn Debug>::new_in_place_from_bytes::<Packet>(buf) };
+    assert_eq!(packet.value_bytes().as_ptr().cast(), value_addr);
+    assert_eq!(
+        format!("{:?}", packet.as_ref()),
+        format!(
+            "{:?}",
+            Packet {
+                id: 7,
+                payload: *b"hello, world"
+            }
+        )
+    );
+}
+
+#[test]
+fn test_box_fmt_pointer() {
+    let mut mem = [0_u8; 64];
+    let buf = mem.as_ptr_range();
+    let val = Box::<dyn Display>::new(&mut mem[1..], 42_u64);
+
+    let addr = format!("{:p}", val);
+    let addr = usize::from_str_radix(addr.trim_start_matches("0x"), 16).unwrap();
+    assert!((buf.start as usize..buf.end as usize).contains(&addr));
+    assert_eq!(addr, val.value_bytes().as_ptr() as usize);
+}
+
+#[test]
+fn test_align_helpers_16_bit_boundary() {
+    use std::convert::TryFrom;
+
+    use crate::align::{
+        checked_align_up_u16, checked_align_up_u8, padding_needed_u16, padding_needed_u8,
+    };
+
+    // The same helpers instantiated for `u16` behave as on a 16-bit target.
+    assert_eq!(padding_needed_u16(0xFFF3, 8), 5);
+    assert_eq!(padding_needed_u16(u16::MAX, 2), 1);
+    assert_eq!(checked_align_up_u16(0xFFF3, 8), Some(0xFFF8));
+    assert_eq!(checked_align_up_u16(0xFFF8, 8), Some(0xFFF8));
+    assert_eq!(checked_align_up_u16(0xFFF9, 8), None);
+    assert_eq!(checked_align_up_u16(0xFFF1, 16), None);
+    assert_eq!(checked_align_up_u16(u16::MAX, 1), Some(u16::MAX));
+    assert_eq!(checked_align_up_u8(0xF1, 16), None);
+    assert_eq!(checked_align_up_u8(0xEF, 16), Some(0xF0));
+    assert_eq!(padding_needed_u8(0xEF, 16), 1);
+
+    // Compare with the same computation in the wider integers.
+    for &align in &[1_u16, 2, 4, 8, 16, 32, 0x8000] {
+        for addr in (u16::MAX - 128)..=u16::MAX {
+            let wide_aligned = (u32::from(addr) + u32::from(align) - 1) & !(u32::from(align) - 1);
+            let expected = u16::try_from(wide_aligned).ok();
+            assert_eq!(checked_align_up_u16(addr, align), expected);
+            assert_eq!(
+                u32::from(padding_needed_u16(addr, align)),
+                wide_aligned - u32::from(addr)
+            );
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "The aligned address overflows `usize`")]
+fn test_align_up_overflow() {
+    align_up(usize::MAX - 6, 8);
+}
+
+#[test]
+#[should_panic(expected = "Not enough memory to store the specified values of type `u64`")]
+fn test_boxed_slice_len_overflow() {
+    let mut mem = [0_u8; 64];
+    Box::<dyn Display>::new_boxed_slice(&mut mem, (0..usize::MAX / 2).map(|_| 0_u64));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "The bytes overlap with the provided buffer")]
+fn test_box_from_value_bytes_overlapping() {
+    let mut source_mem = [0_u8; 32];
+    let meta = Box::<dyn Debug>::new(&mut source_mem, 42_u32).metadata();
+
+    let mut mem = [0_u8; 64];
+    // Only the address of the bytes is taken, no aliasing references are created, and
+    // the bytes are never read, since the check panics before copying them.
+    let bytes = mem[8..].as_ptr().cast::<MaybeUninit<u8>>();
+    unsafe { Box::<dyn Debug>::from_value_bytes(&mut mem, bytes, 4, meta) };
+}
+
+#[test]
+fn test_box_uses_first_aligned_window() {
+    #[repr(align(16))]
+    struct Aligned16([u8; 64]);
+
+    #[derive(Debug)]
+    #[repr(align(16))]
+    struct Value(u8);
+
+    let mut mem = Aligned16([0; 64]);
+    let layout = Box::<dyn Debug>::layout_of::<Value>();
+    let needed = 15 + layout.size();
+
+    // Only the window starting at the 16th byte is aligned and the buffer just fits it.
+    let buf = &mut mem.0[1..16 + layout.size()];
+    assert_eq!(buf.len(), needed);
+    let val = Box::<dyn Debug>::new(buf, Value(42));
+    assert_eq!(val.alignment_waste(), 15);
+    assert_eq!(format!("{:?}", val.as_ref()), "Value(42)");
+    drop(val);
+
+    // A byte less, and there is no aligned window that fits the record.
+    let buf = &mut mem.0[1..15 + layout.size()];
+    let (_, err) = Box::<dyn Debug>::try_new(buf, Value(42)).err().unwrap();
+    assert_eq!(err.needed, needed);
+}
+
+#[test]
+fn test_box_new_not_inlined() {
+    #[inline(never)]
+    fn make_box(mem: &mut [u8], value: u64) -> Box<'_, dyn Display> {
+        Box::new(mem, value)
+    }
+
+    let mut mem = [0_u8; 64];
+    for offset in 0..8 {
+        let buf = &mut mem[offset..];
+        let start = buf.as_ptr() as usize;
+        let val = make_box(buf, offset as u64);
+        assert_eq!((start + val.alignment_waste()) % 8, 0);
+        assert!(unsafe { val.validate() });
+        assert_eq!(val.to_string(), offset.to_string());
+    }
+}
+
+#[test]
+fn test_box_new_with_handle() {
+    trait Greeter {
+        fn greet(&self) -> String;
+    }
+
+    struct Configurable {
+        greeting: &'static str,
+        times: usize,
+    }
+
+    impl Greeter for Configurable {
+        fn greet(&self) -> String {
+            vec![self.greeting; self.times].join(" ")
+        }
+    }
+
+    let mut mem = [0_u8; 64];
+    let mut greeter: TypedBox<dyn Greeter, Configurable> = Box::new_with_handle(
+        &mut mem[1..],
+        Configurable {
+            greeting: "hi",
+            times: 1,
+        },
+    );
+    assert_eq!(greeter.greet(), "hi");
+
+    greeter.value_mut().times = 3;
+    assert_eq!(greeter.value().times, 3);
+    assert_eq!(greeter.greet(), "hi hi hi");
+
+    greeter.update(|greeter| assert_eq!(greeter.greet(), "hi hi hi"));
+    assert_eq!(greeter.as_mut().greet(), "hi hi hi");
+
+    let greeter = greeter.into_box();
+    assert_eq!(greeter.greet(), "hi hi hi");
+}
+
+#[test]
+fn test_box_trait_with_lifetime_generic_method() {
+    #[derive(Debug, PartialEq)]
+    enum Token<'a> {
+        Word(&'a str),
+        Number(u32),
+    }
+
+    trait Parser {
+        fn parse<'a>(&self, s: &'a str) -> Token<'a>;
+    }
+
+    struct Trimming(char);
+
+    impl Parser for Trimming {
+        fn parse<'a>(&self, s: &'a str) -> Token<'a> {
+            let s = s.trim_matches(self.0);
+            s.parse().map_or(Token::Word(s), Token::Number)
+        }
+    }
+
+    let mut mem = [0_u8; 32];
+    let parser = Box::<dyn Parser>::new(&mut mem, Trimming('_'));
+
+    let input = String::from("__hello__");
+    let token = parser.parse(&input);
+    assert_eq!(token, Token::Word("hello"));
+    assert_eq!(parser.parse("_42_"), Token::Number(42));
+    // The token borrows the input, not the box.
+    drop(parser);
+    assert_eq!(token, Token::Word("hello"));
+}
+
+#[test]
+fn test_box_ref_into_iter() {
+    fn join<'a, I>(values: I) -> String
+    where
+        I: IntoIterator<Item = &'a dyn Display>,
+    {
+        values
+            .into_iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    let mut mem = [0_u8; 32];
+    let val = Box::<dyn Display>::new(&mut mem, 42);
+    assert_eq!((&val).into_iter().count(), 1);
+    assert_eq!(join(&val), "42");
+
+    let values: [&dyn Display; 2] = [&1, &'x'];
+    assert_eq!(join(values.iter().copied()), "1,x");
+}
+
+#[test]
+fn test_box_buffer_addr() {
+    let mut mem = [0_u8; 64];
+    let buf = &mut mem[3..];
+    let start = buf.as_ptr() as usize;
+
+    let val = Box::<dyn Display>::new(buf, 42_u64);
+    assert_eq!(val.buffer_addr(), start);
+    assert_eq!(
+        val.value_ptr().cast::<u8>() as usize,
+        val.buffer_addr() + val.alignment_waste() + val.value_offset()
+    );
+}
+
+#[test]
+fn test_slab_alloc_and_reuse() {
+    let drops = Cell::new(0);
+    struct Counted<'a>(u32, &'a Cell<u32>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    impl Display for Counted<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    let mut mem = [0_u8; 100];
+    let slab = Slab::<dyn Display + '_>::new(&mut mem[1..], 32);
+    assert_eq!(slab.capacity(), 3);
+
+    let first = slab.alloc(Counted(1, &drops)).ok().unwrap();
+    let second = slab.alloc(Counted(2, &drops)).ok().unwrap();
+    let third = slab.alloc(Counted(3, &drops)).ok().unwrap();
+    assert_eq!(slab.allocated(), 3);
+    assert_eq!([first.index(), second.index(), third.index()], [0, 1, 2]);
+    let rejected = slab.alloc(Counted(4, &drops)).err().unwrap();
+    assert_eq!(rejected.0, 4);
+    drop(rejected);
+    assert_eq!(drops.get(), 1);
+
+    // The freed slots are reused in the reverse order.
+    drop(second);
+    drop(first);
+    assert_eq!(drops.get(), 3);
+    assert_eq!(slab.allocated(), 1);
+
+    let fifth = slab.alloc(Counted(5, &drops)).ok().unwrap();
+    let sixth = slab.alloc(Counted(6, &drops)).ok().unwrap();
+    assert_eq!([fifth.index(), sixth.index()], [0, 1]);
+    assert_eq!(
+        format!("{} {} {}", fifth.as_ref(), sixth.as_ref(), third.as_ref()),
+        "5 6 3"
+    );
+
+    drop((fifth, sixth, third));
+    assert_eq!(drops.get(), 6);
+    assert_eq!(slab.allocated(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Not enough memory")]
+fn test_slab_slot_insufficient() {
+    let mut mem = [0_u8; 64];
+    let slab = Slab::<dyn Debug>::new(&mut mem, 16);
+    let _value = slab.alloc([0_u64; 4]);
+}
+
+#[test]
+fn test_box_update_chain() {
+    trait Uart {
+        fn set_baud(&mut self, baud: u32);
+        fn enable(&mut self);
+        fn describe(&self) -> String;
+    }
+
+    #[derive(Default)]
+    struct Uart1 {
+        baud: u32,
+        enabled: bool,
+    }
+
+    impl Uart for Uart1 {
+        fn set_baud(&mut self, baud: u32) {
+            self.baud = baud;
+        }
+
+        fn enable(&mut self) {
+            self.enabled = true;
+        }
+
+        fn describe(&self) -> String {
+            format!("baud: {}, enabled: {}", self.baud, self.enabled)
+        }
+    }
+
+    let mut mem = [0_u8; 32];
+    let mut uart = Box::<dyn Uart>::new(&mut mem, Uart1::default());
+    let described = uart
+        .update(|w| w.set_baud(9600))
+        .update(|w| w.enable())
+        .describe();
+    assert_eq!(described, "baud: 9600, enabled: true");
+}
+
+#[test]
+fn test_box_new_copy() {
+    #[derive(Debug, Clone, Copy)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let mut mem = [0_u8; 32];
+    for i in 0..3 {
+        let mut point = Box::<dyn Debug>::new_copy(&mut mem[1..], Point { x: i, y: -i });
+        assert_eq!(
+            format!("{:?}", point.as_mut()),
+            format!("Point {{ x: {}, y: {} }}", i, -i)
+        );
+    }
+
+    let point = Box::<dyn Debug>::new_copy(&mut mem, Point { x: 1, y: 2 }).into_box();
+    assert_eq!(format!("{:?}", point.as_ref()), "Point { x: 1, y: 2 }");
+}
+
+#[test]
+fn test_box_insufficient_memory_reason() {
+    let mut mem = Aligned::<A16, _>::new([0_u8; 64]);
+    let size = Box::<dyn Display>::layout_of::<u64>().size();
+
+    // The record doesn't fit even into the aligned buffer.
+    let (_, err) = Box::<dyn Display>::try_new(&mut mem[..size - 1], 42_u64)
+        .err()
+        .unwrap();
+    assert_eq!(err.reason, Reason::Size);
+    assert_eq!(err.needed, size);
+
+    // The record fits by the size, but the alignment padding pushes it out.
+    let (_, err) = Box::<dyn Display>::try_new(&mut mem[1..=size], 42_u64)
+        .err()
+        .unwrap();
+    assert_eq!(err.reason, Reason::Alignment);
+    assert_eq!(err.available, size);
+    assert_eq!(err.needed, size + 7);
+
+    let err = TypedSlot::<dyn Display, u64>::try_new(&mut mem[1..=size])
+        .err()
+        .unwrap();
+    assert_eq!(err.reason, Reason::Alignment);
+}
+
+#[test]
+fn test_box_new_uninit_garbage() {
+    fn garbage(i: usize) -> u8 {
+        (i as u8).wrapping_mul(151) ^ 0xA5
+    }
+
+    // Simulate a memory region, which isn't zeroed on reset.
+    let mut mem = [MaybeUninit::uninit(); 64];
+    for (i, byte) in mem.iter_mut().enumerate() {
+        *byte = MaybeUninit::new(garbage(i));
+    }
+
+    let val = Box::<dyn Debug>::new_uninit(&mut mem[3..], vec![1, 2, 3]);
+    assert!(unsafe { val.validate() });
+    assert_eq!(format!("{:?}", val.as_ref()), "[1, 2, 3]");
+    let used = 3 + val.used();
+    drop(val);
+
+    // The bytes past the stored record are left untouched.
+    for (i, byte) in mem.iter().enumerate().skip(used) {
+        assert_eq!(unsafe { byte.assume_init() }, garbage(i));
+    }
+}
+
+#[test]
+fn test_box_new_uninit_never_initialized() {
+    // The buffer is never written before the box is created.
+    let mut mem = [MaybeUninit::<u8>::uninit(); 64];
+
+    let val = Box::<dyn Debug>::new_uninit(&mut mem[3..], (7_u8, String::from("value")));
+    assert!(unsafe { val.validate() });
+    assert_eq!(format!("{:?}", val.as_ref()), "(7, \"value\")");
+}
+
+#[test]
+fn test_copy_box_copy_to() {
+    #[derive(Debug, Clone, Copy)]
+    struct Sample {
+        id: u8,
+        value: u64,
+    }
+
+    let mut mem = [0_u8; 32];
+    let mut copy_mems = [[0_u8; 32]; 3];
+
+    let original = Box::<dyn Debug>::new_copy(&mut mem[1..], Sample { id: 1, value: 42 });
+    let copies = copy_mems
+        .iter_mut()
+        .enumerate()
+        .map(|(i, copy_mem)| original.copy_to(&mut copy_mem[i..]))
+        .collect::<Vec<_>>();
+
+    for copy in &copies {
+        assert_eq!(
+            format!("{:?}", copy.as_ref()),
+            "Sample { id: 1, value: 42 }"
+        );
+        assert_ne!(
+            copy.as_ref() as *const dyn Debug as *const u8,
+            original.as_ref() as *const dyn Debug as *const u8
+        );
+    }
+}
+
+#[test]
+fn test_box_pin_receiver_unpin() {
+    use std::pin::Pin;
+
+    trait Step {
+        fn step(self: Pin<&mut Self>) -> u32;
+    }
+
+    struct Counter(u32);
+
+    impl Step for Counter {
+        fn step(mut self: Pin<&mut Self>) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    // The value is `Unpin`, so it can be pinned through a plain mutable reference.
+    let mut mem = [0_u8; 32];
+    let mut counter = Box::<dyn Step + Unpin>::new(&mut mem, Counter(0));
+    assert_eq!(Pin::new(counter.as_mut()).step(), 1);
+    assert_eq!(Pin::new(&mut *counter).step(), 2);
+}
+
+#[test]
+fn test_box_buffer_mut_wipe_spare() {
+    let mut mem = [0xEE_u8; 64];
+    let mut val = Box::<dyn Display>::new(&mut mem[1..], 42_u64);
+    let (padding, used) = (val.alignment_waste(), val.used());
+
+    let buf = unsafe { val.buffer_mut() };
+    assert_eq!(buf.len(), 63);
+    buf[..padding].fill(0);
+    buf[used..].fill(0);
+    assert_eq!(val.to_string(), "42");
+    drop(val);
+
+    assert!(mem[1..1 + padding].iter().all(|&byte| byte == 0));
+    assert!(mem[1 + used..].iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn test_box_from_manually_drop() {
+    let drops = Cell::new(0);
+    struct Counted<'a>(&'a Cell<u32>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    impl Debug for Counted<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Counted")
+        }
+    }
+
+    let value = ManuallyDrop::new(Counted(&drops));
+    let mut mem = [0_u8; 32];
+    let val = Box::<dyn Debug + '_>::from_manually_drop(&mut mem, value);
+    assert_eq!(drops.get(), 0);
+    assert_eq!(format!("{:?}", val.as_ref()), "Counted");
+
+    drop(val);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_box_dyn_fn_mut_state() {
+    let mut mem = [0_u8; 32];
+    let mut count = 0;
+    let mut counter = Box::<dyn FnMut() -> usize>::new(&mut mem, move || {
+        count += 1;
+        count
+    });
+
+    assert_eq!(counter(), 1);
+    assert_eq!(counter(), 2);
+    assert_eq!((&mut *counter)(), 3);
+    assert_eq!(counter.as_mut()(), 4);
+    assert_eq!(counter(), 5);
+}
+
+#[test]
+fn test_static_box_global() {
+    static LOGGER: StaticBox<dyn Fn(&str) -> String + Send + Sync, 32> = StaticBox::new();
+
+    fn log(message: &str) -> Option<String> {
+        LOGGER.get().map(|logger| logger(message))
+    }
+
+    assert_eq!(log("ignored"), None);
+    let prefix = "[INFO]";
+    LOGGER
+        .init(move |message: &str| format!("{} {}", prefix, message))
+        .ok()
+        .unwrap();
+    assert!(LOGGER.init(|message: &str| message.to_owned()).is_err());
+
+    let handle = thread::spawn(|| log("from thread"));
+    assert_eq!(handle.join().unwrap().unwrap(), "[INFO] from thread");
+    assert_eq!(log("from main").unwrap(), "[INFO] from main");
+}
+
+#[test]
+fn test_static_box_init_race() {
+    static VALUE: StaticBox<dyn Display + Send + Sync, 32> = StaticBox::new();
+
+    let mut handles = Vec::new();
+    for i in 0..4 {
+        handles.push(thread::spawn(move || VALUE.init(i).is_ok()));
+    }
+    let initialized = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|&ok| ok)
+        .count();
+    assert_eq!(initialized, 1);
+    assert!(VALUE.get().is_some());
+}
+
+#[test]
+fn test_box_new_partial() {
+    trait Sensor {
+        fn reading(&self) -> Option<u32>;
+    }
+
+    #[derive(Default)]
+    struct Adc {
+        raw: [u16; 4],
+        scale: u32,
+    }
+
+    impl Sensor for Adc {
+        fn reading(&self) -> Option<u32> {
+            if self.scale == 0 {
+                return None;
+            }
+            let sum = self.raw.iter().map(|&raw| u32::from(raw)).sum::<u32>();
+            Some(sum * self.scale / self.raw.len() as u32)
+        }
+    }
+
+    let mut mem = [0_u8; 64];
+    let mut sensor = Box::<dyn Sensor>::new_partial::<Adc>(&mut mem[1..]);
+    assert_eq!(sensor.reading(), None);
+
+    // The first stage fills the samples, e.g. by a DMA transfer.
+    sensor.value_mut().raw = [10, 20, 30, 40];
+    assert_eq!(sensor.reading(), None);
+    // The second stage completes the initialization.
+    sensor.value_mut().scale = 2;
+
+    let sensor = sensor.into_box();
+    assert_eq!(sensor.reading(), Some(50));
+}
+
+#[test]
+fn test_box_as_non_null_intrusive_list() {
+    trait Node {
+        fn name(&self) -> &str;
+        fn next(&self) -> &Cell<Option<NonNull<dyn Node>>>;
+    }
+
+    struct Named {
+        name: &'static str,
+        next: Cell<Option<NonNull<dyn Node>>>,
+    }
+
+    impl Node for Named {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn next(&self) -> &Cell<Option<NonNull<dyn Node>>> {
+            &self.next
+        }
+    }
+
+    let mut first_mem = [0_u8; 64];
+    let mut second_mem = [0_u8; 64];
+    let first = Box::<dyn Node>::new(
+        &mut first_mem,
+        Named {
+            name: "first",
+            next: Cell::new(None),
+        },
+    );
+    let second = Box::<dyn Node>::new(
+        &mut second_mem,
+        Named {
+            name: "second",
+            next: Cell::new(None),
+        },
+    );
+    first.next().set(Some(second.as_non_null()));
+    // Moving the box doesn't move the value out of the buffer.
+    let second = [second];
+
+    let mut names = Vec::new();
+    let mut cursor = Some(first.as_non_null());
+    while let Some(node) = cursor {
+        // Both boxes outlive the traversal.
+        let node = unsafe { node.as_ref() };
+        names.push(node.name());
+        cursor = node.next().get();
+    }
+    assert_eq!(names, ["first", "second"]);
+    assert_eq!(
+        second[0].as_non_null().cast::<()>(),
+        first.next().get().unwrap().cast::<()>()
+    );
+}
+
+#[test]
+fn test_box_same_type() {
+    let mut mem = [[0_u8; 32]; 5];
+    let [number_mem, other_number_mem, wide_number_mem, text_mem, pair_mem] = &mut mem;
+
+    let number = Box::<dyn Debug>::new(number_mem, 1_u32);
+    let other_number = Box::<dyn Debug>::new(other_number_mem, 2_u32);
+    let wide_number = Box::<dyn Debug>::new(wide_number_mem, 1_u64);
+    let text = Box::<dyn Debug>::new(text_mem, "1");
+    let pair = Box::<dyn Debug>::new(pair_mem, (1_u32, 2_u32));
+
+    assert!(number.same_type(&number));
+    assert!(number.same_type(&other_number));
+    assert!(other_number.same_type(&number));
+
+    assert!(!number.same_type(&wide_number));
+    assert!(!number.same_type(&text));
+    assert!(!number.same_type(&pair));
+    assert!(!text.same_type(&pair));
+}
+
+#[test]
+fn test_box_hasher() {
+    struct Fnv(u64);
+
+    impl Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+
+    fn hash_with(hasher: &mut impl Hasher, value: impl Hash) -> u64 {
+        value.hash(hasher);
+        hasher.finish()
+    }
+
+    let mut mem = [0_u8; 128];
+    let mut hasher = Box::<dyn Hasher>::new(&mut mem, Fnv(0xcbf2_9ce4_8422_2325));
+    hasher.write(b"a");
+    assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    drop(hasher);
+
+    let mut hasher = Box::<dyn Hasher>::new(&mut mem, DefaultHasher::new());
+    let mut expected = DefaultHasher::new();
+    assert_eq!(
+        hash_with(&mut hasher, ("key", 42_u32)),
+        hash_with(&mut expected, ("key", 42_u32))
+    );
+}
+
+#[test]
+fn test_box_spare_writer() {
+    let mut mem = [0_u8; 48];
+    let mut value = Box::<dyn Display>::new(&mut mem, 7_u64);
+    let spare = 48 - value.used();
+
+    let mut writer = value.spare_writer();
+    assert_eq!(writer.remaining(), spare);
+    write!(writer, "{}+{}", 1, 2).unwrap();
+    write!(writer, "={}", 3).unwrap();
+    assert_eq!(writer.as_str(), "1+2=3");
+    assert_eq!(writer.remaining(), spare - 5);
+
+    // The text that doesn't fit is rejected, the written text is kept.
+    let long = "x".repeat(spare);
+    assert!(writer.write_str(&long).is_err());
+    assert_eq!(writer.as_str(), "1+2=3");
+
+    writer.clear();
+    writer.write_str(&long).unwrap();
+    assert_eq!(writer.remaining(), 0);
+    assert!(writer.write_char('y').is_err());
+    assert_eq!(writer.as_str(), long);
+
+    // The stored value is not affected by the writes.
+    assert_eq!(value.to_string(), "7");
+    let text = &unsafe { value.buffer_mut() }[48 - spare..];
+    assert_eq!(text, long.as_bytes());
+}
+
+#[test]
+fn test_box_stored_type_id() {
+    let mut mem = [0_u8; 32];
+    let mut other_mem = [0_u8; 32];
+
+    let mut number = Box::<dyn Display>::new_typed(&mut mem, 42_u32);
+    assert_eq!(number.stored_type_id(), Some(TypeId::of::<u32>()));
+    assert_ne!(number.stored_type_id(), Some(TypeId::of::<u64>()));
+    assert_eq!(number.to_string(), "42");
+
+    let mut untyped = Box::<dyn Display>::new(&mut other_mem, 'x');
+    assert_eq!(untyped.stored_type_id(), None);
+
+    // The type follows the stored value.
+    number.swap(&mut untyped);
+    assert_eq!(number.stored_type_id(), None);
+    assert_eq!(untyped.stored_type_id(), Some(TypeId::of::<u32>()));
+    assert_eq!(untyped.to_string(), "42");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_box_from_alloc_box() {
+    trait Checksum {
+        fn checksum(&self) -> u64;
+    }
+
+    struct Table {
+        rows: [u64; 512],
+    }
+
+    impl Checksum for Table {
+        fn checksum(&self) -> u64 {
+            self.rows.iter().sum()
+        }
+    }
+
+    impl Drop for Table {
+        fn drop(&mut self) {
+            DROPS.with(|drops| drops.set(drops.get() + 1));
+        }
+    }
+
+    thread_local! {
+        static DROPS: Cell<usize> = Cell::new(0);
+    }
+
+    static MEM: StaticMem<{ 8 * 512 + 16 }> = StaticMem::new();
+
+    let mut table = std::boxed::Box::new(Table { rows: [0; 512] });
+    for (i, row) in table.rows.iter_mut().enumerate() {
+        *row = i as u64;
+    }
+
+    let table = Box::<dyn Checksum>::from_alloc_box(MEM.take().unwrap(), table);
+    // The value is moved, not dropped.
+    assert_eq!(DROPS.with(Cell::get), 0);
+    assert_eq!(table.checksum(), 511 * 512 / 2);
+
+    drop(table);
+    assert_eq!(DROPS.with(Cell::get), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_box_into_alloc_box() {
+    fn make_counter() -> std::boxed::Box<dyn FnMut() -> usize> {
+        let mut mem = [0_u8; 64];
+        let mut count = 10;
+        let counter = Box::<dyn FnMut() -> usize>::new(&mut mem, move || {
+            count += 1;
+            count
+        });
+        // The closure outlives the buffer on the stack.
+        counter.into_alloc_box()
+    }
+
+    let mut counter = make_counter();
+    assert_eq!(counter(), 11);
+    assert_eq!(counter(), 12);
+
+    // The stored value is dropped once, by the heap box.
+    struct Guard(std::rc::Rc<Cell<usize>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    impl Debug for Guard {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Guard")
+        }
+    }
+
+    let drops = std::rc::Rc::new(Cell::new(0));
+    let mut mem = [0_u8; 32];
+    let guard = Box::<dyn Debug>::new(&mut mem, Guard(drops.clone())).into_alloc_box();
+    assert_eq!(drops.get(), 0);
+    assert_eq!(format!("{:?}", guard), "Guard");
+    drop(guard);
+    assert_eq!(drops.get(), 1);
+
+    // Zero sized values don't allocate.
+    let mut mem = [0_u8; 16];
+    let unit = Box::<dyn Debug>::new(&mut mem, ()).into_alloc_box();
+    assert_eq!(format!("{:?}", unit), "()");
+}
+
+#[test]
+fn test_box_on_drop_hook() {
+    thread_local! {
+        static EVENTS: std::cell::RefCell<Vec<&'static str>> = Default::default();
+    }
+
+    fn record(event: &'static str) {
+        EVENTS.with(|events| events.borrow_mut().push(event));
+    }
+
+    fn events() -> Vec<&'static str> {
+        EVENTS.with(|events| events.borrow().clone())
+    }
+
+    struct Resource;
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            record("resource");
+        }
+    }
+
+    impl Display for Resource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("resource")
+        }
+    }
+
+    let mut mem = [0_u8; 32];
+    let mut value = Box::<dyn Display>::new(&mut mem, Resource);
+    value.on_drop(|| record("first hook"));
+    // The hook is replaced by the later registration.
+    value.on_drop(|| record("hook"));
+    assert!(events().is_empty());
+
+    drop(value);
+    assert_eq!(events(), ["resource", "hook"]);
+
+    // The hook is not called if the value is moved out of the box.
+    let mut value = Box::<dyn Any + Send>::new(&mut mem, 42_u32);
+    value.on_drop(|| record("moved"));
+    assert_eq!(value.downcast_send::<u32>().ok(), Some(42));
+    assert_eq!(events(), ["resource", "hook"]);
+}
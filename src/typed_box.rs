@@ -0,0 +1,128 @@
+use core::{
+    marker::{PhantomData, Unsize},
+    ops::Deref,
+};
+
+use crate::{Box, DynTrait};
+
+/// A box that remembers the concrete type of the stored value, so the value can be accessed
+/// without downcasting.
+///
+/// The box is accessible through [`Deref`] as a regular [`Box`], and the concrete value
+/// through the [`value`](Self::value) and [`value_mut`](Self::value_mut) methods. Both
+/// are borrowed from the typed box, so they never alias.
+///
+/// The inner box is accessible only by a shared reference, otherwise it could be swapped
+/// with a box storing a value of a different type:
+///
+/// ```compile_fail,E0596
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// let mut other_mem = [0_u8; 32];
+/// let mut value = Box::<dyn Display>::new_with_handle(&mut mem, 42_u64);
+/// let mut other = Box::<dyn Display>::new(&mut other_mem, "text");
+/// value.swap(&mut other);
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::Box;
+///
+/// let mut mem = [0_u8; 32];
+/// let mut value = Box::<dyn Display>::new_with_handle(&mut mem, 41_u32);
+/// *value.value_mut() += 1;
+/// assert_eq!(value.to_string(), "42");
+///
+/// let value: Box<dyn Display> = value.into_box();
+/// assert_eq!(value.to_string(), "42");
+/// ```
+pub struct TypedBox<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    inner: Box<'m, T>,
+    phantom: PhantomData<V>,
+}
+
+impl<'m, T, V> TypedBox<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    #[inline]
+    pub(crate) fn new(inner: Box<'m, T>) -> Self {
+        Self {
+            inner,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the stored value.
+    #[inline]
+    pub fn value(&self) -> &V {
+        unsafe { &*self.inner.value_ptr().cast::<V>() }
+    }
+
+    /// Returns a mutable reference to the stored value.
+    #[inline]
+    pub fn value_mut(&mut self) -> &mut V {
+        unsafe { &mut *self.inner.value_mut_ptr().cast::<V>() }
+    }
+
+    /// Calls the specified closure with a mutable reference to the stored value as `T`,
+    /// see [`Box::update`].
+    #[inline]
+    pub fn update<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut T),
+    {
+        self.inner.update(f);
+        self
+    }
+
+    /// Forgets the concrete type of the stored value and returns the regular box.
+    #[inline]
+    pub fn into_box(self) -> Box<'m, T> {
+        self.inner
+    }
+}
+
+impl<'m, T, V> AsRef<T> for TypedBox<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self.inner.as_ref()
+    }
+}
+
+impl<'m, T, V> AsMut<T> for TypedBox<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        self.inner.as_mut()
+    }
+}
+
+impl<'m, T, V> Deref for TypedBox<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    type Target = Box<'m, T>;
+
+    #[inline]
+    fn deref(&self) -> &Box<'m, T> {
+        &self.inner
+    }
+}
@@ -0,0 +1,175 @@
+use core::{
+    marker::{PhantomData, Unsize},
+    mem::ManuallyDrop,
+    ptr::{self, DynMetadata},
+};
+
+use crate::{Box, DynTrait, InsufficientMemory};
+
+/// A slot in the provided memory for repeated placements of values of the same concrete type.
+///
+/// The dynamic metadata is written only once when the slot is created, and each placement
+/// writes only the value itself. The memory layout of the slot is the same as of a [`Box`],
+/// so the placed value can be turned into a box without copying.
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Display;
+/// use static_box::TypedSlot;
+///
+/// let mut mem = [0_u8; 32];
+/// let mut slot = TypedSlot::<dyn Display, u32>::new(&mut mem);
+/// for i in 0..3 {
+///     slot.place(i);
+///     assert_eq!(slot.get().unwrap().to_string(), i.to_string());
+///     slot.clear();
+/// }
+/// ```
+pub struct TypedSlot<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    align_offset: usize,
+    value_offset: usize,
+    occupied: bool,
+    mem: &'m mut [u8],
+    phantom: PhantomData<T>,
+    phantom_value: PhantomData<V>,
+}
+
+impl<'m, T, V> TypedSlot<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    /// Creates an empty slot in the specified `mem` buffer and stores the dynamic metadata
+    /// of the `V` type in it.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided buffer is insufficient to store a value of the `V` type.
+    pub fn new(mem: &'m mut [u8]) -> Self {
+        match Self::try_new(mem) {
+            Ok(slot) => slot,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Attempts to create an empty slot in the specified `mem` buffer.
+    pub fn try_new(mem: &'m mut [u8]) -> Result<Self, InsufficientMemory> {
+        let layout = Box::<T>::layout_of::<V>();
+        let raw_ptr = ptr::addr_of_mut!(*mem).cast::<u8>();
+        let align_offset = Box::<T>::record_align_offset::<V>(raw_ptr as usize, mem.len(), layout)?;
+
+        // Get dynamic metadata for the value type, the pointer itself is never dereferenced.
+        let meta = ptr::metadata(ptr::null::<V>() as *const T);
+        unsafe {
+            raw_ptr
+                .add(align_offset)
+                .cast::<DynMetadata<T>>()
+                .write(meta)
+        };
+
+        Ok(Self {
+            align_offset,
+            value_offset: layout.size() - core::mem::size_of::<V>(),
+            occupied: false,
+            mem,
+            phantom: PhantomData,
+            phantom_value: PhantomData,
+        })
+    }
+
+    /// Places a `value` into the slot, the previously placed value is dropped.
+    #[inline]
+    pub fn place(&mut self, value: V) {
+        self.clear();
+        unsafe { self.value_mut_ptr().write(value) };
+        self.occupied = true;
+    }
+
+    /// Drops the placed value, if any.
+    #[inline]
+    pub fn clear(&mut self) {
+        if self.occupied {
+            // Mark the slot as empty before the drop, so a panicking destructor
+            // doesn't lead to a double drop.
+            self.occupied = false;
+            unsafe { ptr::drop_in_place(self.value_mut_ptr()) };
+        }
+    }
+
+    /// Returns `true` if the slot contains a value.
+    #[inline]
+    pub fn is_occupied(&self) -> bool {
+        self.occupied
+    }
+
+    /// Returns a reference to the placed value, or `None` if the slot is empty.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        if self.occupied {
+            let value: &T = unsafe { &*self.value_ptr() };
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the placed value, or `None` if the slot is empty.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.occupied {
+            let value: &mut T = unsafe { &mut *self.value_mut_ptr() };
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Converts the slot into a regular box owning the placed value,
+    /// or returns `None` if the slot is empty.
+    pub fn into_box(self) -> Option<Box<'m, T>> {
+        if !self.occupied {
+            return None;
+        }
+
+        // The placed value is moved into the box, so the slot must not drop it.
+        let this = ManuallyDrop::new(self);
+        let mem = unsafe { ptr::read(&this.mem) };
+        Some(Box::from_raw_parts(mem, this.align_offset))
+    }
+
+    #[inline]
+    fn value_ptr(&self) -> *const V {
+        unsafe {
+            self.mem
+                .as_ptr()
+                .add(self.align_offset + self.value_offset)
+                .cast()
+        }
+    }
+
+    #[inline]
+    fn value_mut_ptr(&mut self) -> *mut V {
+        unsafe {
+            self.mem
+                .as_mut_ptr()
+                .add(self.align_offset + self.value_offset)
+                .cast()
+        }
+    }
+}
+
+impl<'m, T, V> Drop for TypedSlot<'m, T, V>
+where
+    T: ?Sized + DynTrait,
+    V: Unsize<T>,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}